@@ -1,5 +1,6 @@
 use crate::models::HookType;
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(
@@ -23,6 +24,10 @@ Example workflow:
     author
 )]
 pub struct Cli {
+    /// Run as if hookman was started in <PATH> instead of the current directory
+    #[arg(short = 'C', long = "repo", value_name = "PATH", global = true)]
+    pub repo: Option<PathBuf>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -52,8 +57,10 @@ Example:
     #[command(long_about = "Add a command to a specific Git hook type.
 
 Each command must have a unique ID within the hook. Commands are executed
-in the order they were added. If a command fails (non-zero exit code),
-subsequent commands will not be executed.
+in the order they were added, unless --position inserts one elsewhere; see
+also 'hookman move'. If a command fails (non-zero exit code),
+subsequent commands will not be executed unless the hook sets
+fail_fast = false.
 
 Examples:
   # Add a formatting check to pre-commit
@@ -71,13 +78,17 @@ Examples:
         /// The command to execute
         command: String,
 
-        /// Unique identifier for this command
+        /// Unique identifier for this command: letters, digits, '-', '_' and '.'
         #[arg(short, long)]
         id: String,
 
         /// Human-readable description of what this command does
         #[arg(short, long)]
         description: Option<String>,
+
+        /// Insert at this position instead of appending, 1 being the first
+        #[arg(long, value_name = "POSITION")]
+        position: Option<usize>,
     },
 
     /// Remove a command from a hook
@@ -97,6 +108,142 @@ Example:
         command_id: String,
     },
 
+    /// Change an existing command in place
+    #[command(
+        long_about = "Change an existing command of a hook, keeping its place in the order.
+
+Each option replaces one field of the command; an empty value clears an
+optional field, e.g. --description ''. Renaming a command with --new-id also
+updates the depends_on lists that refer to it. The --when-* options change
+the conditions in the command's when block the same way.
+
+Without any options, the command's TOML is opened in $VISUAL or $EDITOR
+(falling back to vi). The result is validated before it is saved; if it is
+invalid you can edit it again or give up, leaving the hook unchanged.
+
+Examples:
+  $ hookman edit pre-commit format --command \"cargo fmt --all -- --check\"
+  $ hookman edit pre-push test --new-id unit-tests --timeout 5m
+  $ hookman edit pre-commit lint --files '*.js' --files '*.ts'
+  $ hookman edit pre-push deploy-check --when-branch main --when-ci false
+  $ hookman edit pre-commit lint    # opens $EDITOR"
+    )]
+    Edit {
+        /// The type of Git hook
+        hook_type: HookType,
+
+        /// The ID of the command to change
+        command_id: String,
+
+        #[command(flatten)]
+        changes: Box<CommandChanges>,
+    },
+
+    /// Change where a command runs in a hook's order
+    #[command(long_about = "Move a command to another place in its hook's order.
+
+Commands run in the order they appear in the hook's TOML. Place the command
+just before or after another one, or at a position counted from 1. A command
+still runs after the commands in its depends_on, wherever it is placed.
+
+Examples:
+  $ hookman move pre-commit lint --before test
+  $ hookman move pre-commit format --to 1")]
+    Move {
+        /// The type of Git hook
+        hook_type: HookType,
+
+        /// The ID of the command to move
+        command_id: String,
+
+        #[command(flatten)]
+        placement: Placement,
+    },
+
+    /// Stop a command from running without removing it
+    #[command(
+        long_about = "Disable a command so it no longer runs, keeping its definition.
+
+Disabled commands stay in the hook's TOML with 'enabled = false'. They are
+shown greyed out by 'hookman list', reported as skipped when the hook runs,
+and commands that depend on them are skipped too.
+
+Example:
+  $ hookman disable pre-commit lint
+  ✓ Disabled command 'lint' in pre-commit hook"
+    )]
+    Disable {
+        /// The type of Git hook
+        hook_type: HookType,
+
+        /// The ID of the command to disable
+        command_id: String,
+    },
+
+    /// Let a disabled command run again
+    Enable {
+        /// The type of Git hook
+        hook_type: HookType,
+
+        /// The ID of the command to enable
+        command_id: String,
+    },
+
+    /// Move a command to another hook type
+    #[command(
+        long_about = "Move a command, with all its settings, to the end of another hook.
+
+The target hook must not already have a command with the same ID; use
+--new-id to rename the command on the way. A command that others in its
+hook depend on can't be moved, and any depends_on of the moved command
+must name commands of the target hook.
+
+Example:
+  $ hookman mv pre-commit test pre-push
+  ✓ Moved command 'test' from pre-commit to pre-push hook"
+    )]
+    Mv {
+        /// The hook the command is in
+        from_hook: HookType,
+
+        /// The ID of the command to move
+        command_id: String,
+
+        /// The hook to move it to
+        to_hook: HookType,
+
+        /// ID for the command in the target hook
+        #[arg(long, value_name = "ID")]
+        new_id: Option<String>,
+    },
+
+    /// Copy a command to another hook type
+    #[command(
+        long_about = "Copy a command, with all its settings, to the end of another hook.
+
+The target hook must not already have a command with the same ID; use
+--new-id to give the copy another ID, which also allows copying within the
+same hook.
+
+Example:
+  $ hookman cp pre-commit lint pre-push
+  ✓ Copied command 'lint' from pre-commit to pre-push hook"
+    )]
+    Cp {
+        /// The hook the command is in
+        from_hook: HookType,
+
+        /// The ID of the command to copy
+        command_id: String,
+
+        /// The hook to copy it to
+        to_hook: HookType,
+
+        /// ID for the copy
+        #[arg(long, value_name = "ID")]
+        new_id: Option<String>,
+    },
+
     /// List all hooks or commands for a specific hook
     #[command(
         long_about = "List all configured hooks or commands for a specific hook type.
@@ -119,12 +266,15 @@ Examples:
     /// Apply the hook configuration to the Git repository
     #[command(long_about = "Apply the hook configuration to the Git repository.
 
-This command generates shell scripts from your hook configurations and
-installs them to .git/hooks/. Existing hooks are backed up with a .backup
-extension before being replaced.
+This command installs a small script for each configured hook type into
+.git/hooks/, or into the directory named by core.hooksPath when it is set
+in the local, global or system Git config. Existing hooks are backed up
+in .git/hookman/backups/ before being replaced; see 'hookman backups'.
 
-The generated scripts include error handling (set -e) and will stop
-execution if any command fails.
+The installed scripts call 'hookman run <hook-type>', which loads the
+commands from .hookman/hooks/ each time the hook fires. Editing the
+configuration therefore takes effect immediately; re-run apply only when
+adding a new hook type.
 
 Examples:
   # Apply all configured hooks
@@ -138,6 +288,56 @@ Examples:
         dry_run: bool,
     },
 
+    /// Remove hookman-managed hooks from the Git repository
+    #[command(long_about = "Remove hookman-managed hooks from the Git repository.
+
+Deletes the scripts installed by 'hookman apply' from the Git hooks
+directory. Hooks that were not written by hookman are never touched. If
+apply backed up a previous hook, the latest backup is restored in its place.
+
+The configuration in .hookman/ is left untouched, so 'hookman apply'
+reinstalls the hooks.
+
+Examples:
+  # Uninstall every hookman-managed hook
+  $ hookman uninstall
+
+  # Uninstall only the pre-push hook
+  $ hookman uninstall pre-push
+
+  # Preview what would be removed
+  $ hookman uninstall --dry-run")]
+    Uninstall {
+        /// Hook types to uninstall (defaults to all)
+        hook_types: Vec<HookType>,
+
+        /// Show what would be done without making changes
+        #[arg(short, long)]
+        dry_run: bool,
+    },
+
+    /// List and restore backups of hooks replaced by apply
+    #[command(long_about = "List and restore backups of hooks replaced by apply.
+
+Whenever 'hookman apply' replaces a hook that hookman didn't write, the
+original is saved to .git/hookman/backups/<hook-type>/<id>, where the id is
+a UTC timestamp. Scripts generated by hookman are never backed up, so
+re-applying can't overwrite the original.
+
+Examples:
+  # List all backups
+  $ hookman backups list
+
+  # Restore the most recent pre-push backup
+  $ hookman backups restore pre-push
+
+  # Restore a specific backup
+  $ hookman backups restore pre-push 20261018T143015Z")]
+    Backups {
+        #[command(subcommand)]
+        command: BackupsCommand,
+    },
+
     /// Show the current hook configuration status
     #[command(long_about = "Show the current status of hook configurations.
 
@@ -145,21 +345,194 @@ Displays which hooks are configured, how many commands each has, and
 whether they have been applied to the Git repository. Also shows if
 there are external (non-hookman) hooks present.
 
-Status indicators:
-  • Green: Hook is configured and applied
-  • Red: Hook is configured but not applied
-  • Yellow: External hook exists or no commands configured
+Each installed script records a hash of its own contents, so status can
+tell these states apart:
+  • applied (green): the installed script is what apply would write now
+  • not applied (red): no script is installed for a configured hook
+  • outdated (yellow): installed by an older hookman version, or chain is
+    set but no pre-existing hook has been preserved; re-run apply
+  • modified by hand (red): the installed script was edited after apply
+  • external hook exists (yellow): a script not written by hookman
+  • no commands (yellow): a script is installed but nothing is configured
+  • invalid (red): the configuration has errors, such as a depends_on cycle
+
+Use --check to exit with a non-zero status when any configured hook is not
+in the applied state, e.g. to gate CI:
+  $ hookman status --check
 
 Example:
   $ hookman status
   Hookman Status
   =============
   
+  Repository: /path/to/project
   Configuration directory: /path/to/project/.hookman
   Git hooks directory: /path/to/project/.git/hooks
   
   Configured hooks:
     pre-commit - 2 commands, applied
     pre-push - 1 commands, not applied")]
-    Status,
+    Status {
+        /// Exit with a non-zero status if any hook is not up to date
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Run the commands configured for a hook
+    #[command(long_about = "Run the commands configured for a hook.
+
+This is what the scripts installed by 'hookman apply' call when Git fires a
+hook. Commands are loaded from .hookman/hooks/<hook-type>.toml and run in
+order from the repository root; execution stops at the first failing command
+and hookman exits with a non-zero status. With 'fail_fast = false' in the
+hook's TOML every command runs, and commands with 'allow_failure = true' are
+reported without failing the hook. With 'parallel = true' commands run
+concurrently and each one's output is printed in order once it finishes. A
+summary of each command's result is printed at the end.
+
+Arguments after '--' are passed to every command as $1, $2, ... and as named
+HOOKMAN_* environment variables. For hooks that receive data on stdin, the
+input is read once and replayed to each command.
+
+It can also be run by hand to try a hook without triggering Git:
+  $ hookman run pre-commit
+  $ hookman run commit-msg -- .git/COMMIT_EDITMSG")]
+    Run {
+        /// The type of Git hook
+        hook_type: HookType,
+
+        /// Arguments Git passed to the hook
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BackupsCommand {
+    /// List saved hook backups
+    List {
+        /// Optional: only list backups of this hook type
+        hook_type: Option<HookType>,
+    },
+
+    /// Restore a saved hook backup into the Git hooks directory
+    Restore {
+        /// The type of Git hook
+        hook_type: HookType,
+
+        /// The backup to restore (defaults to the latest)
+        id: Option<String>,
+    },
+}
+
+/// Where `hookman move` puts a command.
+#[derive(Args, Debug)]
+#[group(required = true, multiple = false)]
+pub struct Placement {
+    /// Put the command just before this one
+    #[arg(long, value_name = "ID")]
+    pub before: Option<String>,
+
+    /// Put the command just after this one
+    #[arg(long, value_name = "ID")]
+    pub after: Option<String>,
+
+    /// Put the command at this position, 1 being the first
+    #[arg(long, value_name = "POSITION")]
+    pub to: Option<usize>,
+}
+
+/// Changes to the fields of a command, as given to `hookman edit`.
+#[derive(Args, Debug, Default, PartialEq)]
+pub struct CommandChanges {
+    /// New ID, also updated in other commands' depends_on
+    #[arg(long, value_name = "ID")]
+    pub new_id: Option<String>,
+
+    /// Command line to run, replacing any script
+    #[arg(long, conflicts_with = "script")]
+    pub command: Option<String>,
+
+    /// Multi-line script to run, replacing the command line
+    #[arg(long)]
+    pub script: Option<String>,
+
+    /// Human-readable description of what this command does
+    #[arg(short, long)]
+    pub description: Option<String>,
+
+    /// Whether the command runs at all
+    #[arg(long, value_name = "BOOL")]
+    pub enabled: Option<bool>,
+
+    /// Kill the command after this long, e.g. 90s or 5m
+    #[arg(long, value_name = "DURATION")]
+    pub timeout: Option<String>,
+
+    /// Report a failure without failing the hook
+    #[arg(long, value_name = "BOOL")]
+    pub allow_failure: Option<bool>,
+
+    /// Whether the command rewrites files
+    #[arg(long, value_name = "BOOL")]
+    pub fixer: Option<bool>,
+
+    /// Commands that must pass first; repeat for several
+    #[arg(long, value_name = "ID")]
+    pub depends_on: Option<Vec<String>>,
+
+    /// Only run when changed files match; repeat for several globs
+    #[arg(long, value_name = "GLOB")]
+    pub files: Option<Vec<String>>,
+
+    /// Ignore changed files matching; repeat for several globs
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Option<Vec<String>>,
+
+    /// Directory to run in, relative to the repository root
+    #[arg(long, value_name = "DIR")]
+    pub cwd: Option<String>,
+
+    /// Interpreter to run the command with, e.g. bash or python3
+    #[arg(long)]
+    pub shell: Option<String>,
+
+    /// Set an environment variable; repeat for several
+    #[arg(long, value_name = "NAME=VALUE", value_parser = parse_env_var)]
+    pub env: Vec<(String, String)>,
+
+    /// Remove an environment variable; repeat for several
+    #[arg(long, value_name = "NAME")]
+    pub unset_env: Vec<String>,
+
+    /// Only run on branches matching; repeat for several globs
+    #[arg(long, value_name = "GLOB")]
+    pub when_branch: Option<Vec<String>>,
+
+    /// Only run when pushing to this remote
+    #[arg(long, value_name = "REMOTE")]
+    pub when_remote: Option<String>,
+
+    /// Only run in CI (true) or outside it (false)
+    #[arg(long, value_name = "BOOL", value_parser = ["true", "false", ""])]
+    pub when_ci: Option<String>,
+
+    /// Only run when an environment variable has this value; repeat for several
+    #[arg(long, value_name = "NAME=VALUE", value_parser = parse_env_var)]
+    pub when_env: Vec<(String, String)>,
+
+    /// Remove a --when-env condition; repeat for several
+    #[arg(long, value_name = "NAME")]
+    pub unset_when_env: Vec<String>,
+
+    /// In post-checkout, only run for branch or file checkouts
+    #[arg(long, value_name = "TYPE", value_parser = ["branch", "file", ""])]
+    pub when_checkout_type: Option<String>,
+}
+
+fn parse_env_var(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+        _ => Err(format!("expected NAME=VALUE, got '{}'", value)),
+    }
 }
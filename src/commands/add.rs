@@ -1,21 +1,26 @@
 use crate::error::HookmanError;
+use crate::git::Repository;
 use crate::models::{Command, HookType};
 use crate::storage::{Storage, TomlStorage};
 use anyhow::Result;
 use colored::Colorize;
 
 pub fn execute(
+    repo: &Repository,
     hook_type: HookType,
     command: String,
     id: String,
     description: Option<String>,
+    position: Option<usize>,
 ) -> Result<()> {
-    let storage = TomlStorage::new();
+    let storage = TomlStorage::new(repo.hookman_dir());
 
     if !storage.is_initialized() {
         return Err(HookmanError::NotInitialized.into());
     }
 
+    Command::validate_id(&id)?;
+
     let mut hook = storage.load_hook(hook_type)?;
 
     // Check if command with this ID already exists
@@ -23,9 +28,24 @@ pub fn execute(
         return Err(HookmanError::CommandAlreadyExists(id, hook_type.to_string()).into());
     }
 
+    // Positions count from 1; one past the end appends.
+    let index = match position {
+        Some(position) if position == 0 || position > hook.commands.len() + 1 => {
+            return Err(HookmanError::InvalidPosition(
+                position,
+                hook_type.to_string(),
+                hook.commands.len() + 1,
+            )
+            .into());
+        }
+        Some(position) => position - 1,
+        None => hook.commands.len(),
+    };
+
     let new_command = Command::new(id.clone(), command.clone(), description.clone());
-    hook.commands.push(new_command);
+    hook.commands.insert(index, new_command);
 
+    hook.validate()?;
     storage.save_hook(&hook)?;
 
     println!(
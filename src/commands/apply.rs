@@ -1,21 +1,23 @@
 use crate::error::HookmanError;
-use crate::generator::ScriptGenerator;
-use crate::storage::{get_git_hooks_dir, Storage, TomlStorage};
+use crate::generator::{is_managed_file, ScriptGenerator};
+use crate::git::Repository;
+use crate::storage::{BackupStore, Storage, TomlStorage};
 use anyhow::Result;
 use colored::Colorize;
 use std::fs;
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 
-pub fn execute(dry_run: bool) -> Result<()> {
-    let storage = TomlStorage::new();
+pub fn execute(repo: &Repository, dry_run: bool) -> Result<()> {
+    let storage = TomlStorage::new(repo.hookman_dir());
 
     if !storage.is_initialized() {
         return Err(HookmanError::NotInitialized.into());
     }
 
-    let git_hooks_dir = get_git_hooks_dir()?;
-    let generator = ScriptGenerator::new();
+    let git_hooks_dir = repo.git_hooks_dir()?;
+    let generator = ScriptGenerator::new(std::env::current_exe()?);
+    let backups = BackupStore::new(repo.backups_dir());
 
     let configured_hooks = storage.list_hooks()?;
 
@@ -27,10 +29,21 @@ pub fn execute(dry_run: bool) -> Result<()> {
     if dry_run {
         println!("{}", "DRY RUN - No changes will be made".yellow().bold());
         println!();
+    } else {
+        fs::create_dir_all(&git_hooks_dir)?;
     }
 
+    // Check every hook before installing any, so a mistake in one doesn't
+    // leave the others half applied.
+    let mut hooks = Vec::new();
     for hook_type in configured_hooks {
         let hook = storage.load_hook(hook_type)?;
+        hook.validate()?;
+        hooks.push(hook);
+    }
+
+    for hook in hooks {
+        let hook_type = hook.hook_type;
 
         if hook.commands.is_empty() {
             continue;
@@ -38,8 +51,43 @@ pub fn execute(dry_run: bool) -> Result<()> {
 
         let script = generator.generate(&hook)?;
         let hook_path = git_hooks_dir.join(hook_type.as_str());
+        // Only back up hooks hookman didn't write, so the user's original is
+        // never replaced by one of hookman's own scripts.
+        let needs_backup = hook_path.exists() && !is_managed_file(&hook_path)?;
+        let chained_path = repo.chained_hooks_dir().join(hook_type.as_str());
+        // In chain mode the original keeps running from a stable location.
+        // It is the hook about to be replaced or, if an earlier apply already
+        // replaced it before chain was set, its latest backup.
+        let chain_source = match hook.chain {
+            Some(_) if needs_backup => Some(hook_path.clone()),
+            Some(_) if !chained_path.exists() => {
+                backups.latest(hook_type)?.map(|backup| backup.path)
+            }
+            _ => None,
+        };
 
         if dry_run {
+            if needs_backup {
+                println!(
+                    "{}",
+                    format!(
+                        "Would back up existing {} to {}",
+                        hook_type,
+                        backups.dir().display()
+                    )
+                    .yellow()
+                );
+            }
+            if let (Some(mode), Some(_)) = (hook.chain, &chain_source) {
+                println!(
+                    "{}",
+                    format!(
+                        "Would chain existing {} to run {} hookman commands",
+                        hook_type, mode
+                    )
+                    .yellow()
+                );
+            }
             println!(
                 "{}",
                 format!("Would create: {}", hook_path.display()).blue()
@@ -50,16 +98,28 @@ pub fn execute(dry_run: bool) -> Result<()> {
             println!("{}", "---".dimmed());
             println!();
         } else {
-            // Backup existing hook if it exists
-            if hook_path.exists() {
-                let backup_path = hook_path.with_extension("backup");
-                fs::copy(&hook_path, &backup_path)?;
+            if needs_backup {
+                if let Some(backup) = backups.save(hook_type, &hook_path)? {
+                    println!(
+                        "{}",
+                        format!(
+                            "  Backed up existing {} to {}",
+                            hook_type,
+                            backup.path.display()
+                        )
+                        .yellow()
+                    );
+                }
+            }
+
+            if let (Some(mode), Some(source)) = (hook.chain, &chain_source) {
+                fs::create_dir_all(repo.chained_hooks_dir())?;
+                fs::copy(source, &chained_path)?;
                 println!(
                     "{}",
                     format!(
-                        "  Backed up existing {} to {}",
-                        hook_type,
-                        backup_path.display()
+                        "  Chaining existing {} to run {} hookman commands",
+                        hook_type, mode
                     )
                     .yellow()
                 );
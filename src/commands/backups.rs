@@ -0,0 +1,87 @@
+use crate::generator::is_managed_file;
+use crate::git::Repository;
+use crate::models::HookType;
+use crate::storage::BackupStore;
+use anyhow::Result;
+use colored::Colorize;
+use std::fs;
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+
+pub fn list(repo: &Repository, hook_type: Option<HookType>) -> Result<()> {
+    let store = BackupStore::new(repo.backups_dir());
+    let backups = store.list(hook_type)?;
+
+    if backups.is_empty() {
+        println!("No backups found");
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!("Backups in {}:", store.dir().display()).bold()
+    );
+
+    let mut current = None;
+    for (index, backup) in backups.iter().enumerate() {
+        if current != Some(backup.hook_type) {
+            println!("\n{}", format!("{}", backup.hook_type).green());
+            current = Some(backup.hook_type);
+        }
+
+        let size = fs::metadata(&backup.path)?.len();
+        let is_latest = backups
+            .get(index + 1)
+            .is_none_or(|next| next.hook_type != backup.hook_type);
+        let latest = if is_latest { " (latest)" } else { "" };
+
+        println!(
+            "  {} {}{}",
+            backup.id.cyan(),
+            format!("{} bytes", size).dimmed(),
+            latest
+        );
+    }
+
+    Ok(())
+}
+
+pub fn restore(repo: &Repository, hook_type: HookType, id: Option<String>) -> Result<()> {
+    let store = BackupStore::new(repo.backups_dir());
+    let backup = store.find(hook_type, id.as_deref())?;
+
+    let git_hooks_dir = repo.git_hooks_dir()?;
+    fs::create_dir_all(&git_hooks_dir)?;
+    let hook_path = git_hooks_dir.join(hook_type.as_str());
+
+    // Never lose a hook hookman didn't write: save it before overwriting.
+    if hook_path.exists() && !is_managed_file(&hook_path)? {
+        if let Some(saved) = store.save(hook_type, &hook_path)? {
+            println!(
+                "{}",
+                format!(
+                    "  Backed up current {} to {}",
+                    hook_type,
+                    saved.path.display()
+                )
+                .yellow()
+            );
+        }
+    }
+
+    store.restore(&backup, &hook_path)?;
+
+    #[cfg(unix)]
+    {
+        let mut perms = fs::metadata(&hook_path)?.permissions();
+        perms.set_mode(perms.mode() | 0o755);
+        fs::set_permissions(&hook_path, perms)?;
+    }
+
+    println!(
+        "{}",
+        format!("✓ Restored {} hook from backup {}", hook_type, backup.id).green()
+    );
+
+    Ok(())
+}
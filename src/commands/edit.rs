@@ -0,0 +1,420 @@
+use crate::cli::CommandChanges;
+use crate::error::HookmanError;
+use crate::git::Repository;
+use crate::models::{CheckoutType, Command, Hook, HookType, When};
+use crate::storage::{Storage, TomlStorage};
+use anyhow::{bail, Result};
+use colored::Colorize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+
+pub fn execute(
+    repo: &Repository,
+    hook_type: HookType,
+    command_id: String,
+    changes: CommandChanges,
+) -> Result<()> {
+    let storage = TomlStorage::new(repo.hookman_dir());
+
+    if !storage.is_initialized() {
+        return Err(HookmanError::NotInitialized.into());
+    }
+
+    let hook = storage.load_hook(hook_type)?;
+    let index = hook
+        .commands
+        .iter()
+        .position(|c| c.id == command_id)
+        .ok_or_else(|| HookmanError::CommandNotFound(command_id, hook_type.to_string()))?;
+
+    let updated = if changes == CommandChanges::default() {
+        match edit_interactively(&hook, index)? {
+            Some(updated) => updated,
+            None => {
+                println!("Edit cancelled, {} hook unchanged", hook_type);
+                return Ok(());
+            }
+        }
+    } else {
+        let mut command = hook.commands[index].clone();
+        apply_changes(&mut command, changes)?;
+        replace(&hook, index, command)?
+    };
+
+    storage.save_hook(&updated)?;
+
+    println!(
+        "{}",
+        format!(
+            "✓ Updated command '{}' in {} hook",
+            updated.commands[index].id, hook_type
+        )
+        .green()
+    );
+
+    Ok(())
+}
+
+fn apply_changes(command: &mut Command, changes: CommandChanges) -> Result<()> {
+    if let Some(id) = changes.new_id {
+        command.id = id;
+    }
+    if let Some(line) = changes.command {
+        command.command = line;
+        command.script = None;
+    }
+    if let Some(script) = changes.script {
+        command.command.clear();
+        command.script = Some(script);
+    }
+    if let Some(description) = changes.description {
+        command.description = non_empty(description);
+    }
+    if let Some(enabled) = changes.enabled {
+        command.enabled = enabled;
+    }
+    if let Some(timeout) = changes.timeout {
+        command.timeout = match non_empty(timeout) {
+            Some(timeout) => match timeout.parse() {
+                Ok(timeout) => Some(timeout),
+                Err(e) => bail!("Invalid timeout '{}': {}", timeout, e),
+            },
+            None => None,
+        };
+    }
+    if let Some(allow_failure) = changes.allow_failure {
+        command.allow_failure = allow_failure;
+    }
+    if let Some(fixer) = changes.fixer {
+        command.fixer = fixer;
+    }
+    if let Some(depends_on) = changes.depends_on {
+        command.depends_on = non_empty_values(depends_on);
+    }
+    if let Some(files) = changes.files {
+        command.files = non_empty_values(files);
+    }
+    if let Some(exclude) = changes.exclude {
+        command.exclude = non_empty_values(exclude);
+    }
+    if let Some(cwd) = changes.cwd {
+        command.cwd = non_empty(cwd).map(PathBuf::from);
+    }
+    if let Some(shell) = changes.shell {
+        command.shell = non_empty(shell);
+    }
+    command.env.extend(changes.env);
+    for name in changes.unset_env {
+        command.env.remove(&name);
+    }
+
+    let changes_when = changes.when_branch.is_some()
+        || changes.when_remote.is_some()
+        || changes.when_ci.is_some()
+        || !changes.when_env.is_empty()
+        || !changes.unset_when_env.is_empty()
+        || changes.when_checkout_type.is_some();
+    if changes_when {
+        let mut when = command.when.take().unwrap_or_default();
+        if let Some(branch) = changes.when_branch {
+            when.branch = non_empty_values(branch);
+        }
+        if let Some(remote) = changes.when_remote {
+            when.remote = non_empty(remote);
+        }
+        if let Some(ci) = changes.when_ci {
+            when.ci = non_empty(ci).map(|ci| ci == "true");
+        }
+        when.env.extend(changes.when_env);
+        for name in changes.unset_when_env {
+            when.env.remove(&name);
+        }
+        if let Some(checkout_type) = changes.when_checkout_type {
+            when.checkout_type = match checkout_type.as_str() {
+                "branch" => Some(CheckoutType::Branch),
+                "file" => Some(CheckoutType::File),
+                _ => None,
+            };
+        }
+        // A when block without conditions is left out of the file.
+        command.when = Some(when).filter(|when| *when != When::default());
+    }
+    Ok(())
+}
+
+/// An empty value given for an optional field clears it.
+fn non_empty(value: String) -> Option<String> {
+    Some(value).filter(|value| !value.is_empty())
+}
+
+/// An empty value given for a list, such as `--files ''`, empties it.
+fn non_empty_values(values: Vec<String>) -> Vec<String> {
+    values
+        .into_iter()
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+/// Returns `hook` with `command` in place of the command at `index`. A new
+/// ID must be valid and unused, and replaces the old one in `depends_on`
+/// lists.
+fn replace(hook: &Hook, index: usize, command: Command) -> Result<Hook> {
+    let mut hook = hook.clone();
+    let old_id = std::mem::take(&mut hook.commands[index].id);
+
+    if command.id != old_id {
+        Command::validate_id(&command.id)?;
+        if hook.commands.iter().any(|c| c.id == command.id) {
+            return Err(
+                HookmanError::CommandAlreadyExists(command.id, hook.hook_type.to_string()).into(),
+            );
+        }
+        for other in &mut hook.commands {
+            for dep in other.depends_on.iter_mut().filter(|dep| **dep == old_id) {
+                *dep = command.id.clone();
+            }
+        }
+    }
+
+    hook.commands[index] = command;
+    hook.validate()?;
+    Ok(hook)
+}
+
+/// Opens the command at `index` in the user's editor until it is valid or
+/// the user gives up, returning the updated hook or `None` if cancelled.
+fn edit_interactively(hook: &Hook, index: usize) -> Result<Option<Hook>> {
+    let contents = toml::to_string_pretty(&hook.commands[index])?;
+    let path = std::env::temp_dir().join(format!("hookman-edit-{}.toml", std::process::id()));
+
+    // The name is predictable, so never open an existing file or symlink
+    // there, and keep the command's env values private.
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(&path)?;
+    let written = file.write_all(contents.as_bytes());
+    drop(file);
+
+    let result = written
+        .map_err(anyhow::Error::from)
+        .and_then(|()| edit_until_valid(hook, index, &path));
+    let _ = fs::remove_file(&path);
+    result
+}
+
+fn edit_until_valid(hook: &Hook, index: usize, path: &Path) -> Result<Option<Hook>> {
+    loop {
+        run_editor(path)?;
+
+        let contents = fs::read_to_string(path)?;
+        let updated = toml::from_str::<Command>(&contents)
+            .map_err(anyhow::Error::from)
+            .and_then(|command| replace(hook, index, command));
+
+        match updated {
+            Ok(updated) => return Ok(Some(updated)),
+            Err(e) => {
+                eprintln!("{}", format!("✗ {}", e).red());
+                if !confirm("Edit again? [Y/n] ")? {
+                    return Ok(None);
+                }
+            }
+        }
+    }
+}
+
+/// Runs `$VISUAL` or `$EDITOR` on `path` through the shell, so editors
+/// configured with arguments (`code --wait`) work.
+fn run_editor(path: &Path) -> Result<()> {
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+
+    let status = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg("sh")
+        .arg(path)
+        .status()?;
+    if !status.success() {
+        bail!("Editor '{}' exited with {}", editor, status);
+    }
+    Ok(())
+}
+
+/// Asks a yes/no question on stderr, defaulting to yes. End of input counts
+/// as no.
+fn confirm(prompt: &str) -> Result<bool> {
+    eprint!("{}", prompt);
+    std::io::stderr().flush()?;
+
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer)? == 0 {
+        return Ok(false);
+    }
+    let answer = answer.trim().to_lowercase();
+    Ok(answer.is_empty() || answer == "y" || answer == "yes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook() -> Hook {
+        let mut hook = Hook::new(HookType::PreCommit);
+        for id in ["fmt", "lint", "test"] {
+            hook.commands
+                .push(Command::new(id.to_string(), format!("make {}", id), None));
+        }
+        hook.commands[2].depends_on = vec!["fmt".to_string(), "lint".to_string()];
+        hook
+    }
+
+    #[test]
+    fn test_apply_changes() {
+        let mut command = Command::new(
+            "test".to_string(),
+            "cargo test".to_string(),
+            Some("Run tests".to_string()),
+        );
+        command.env.insert("OLD".to_string(), "1".to_string());
+
+        apply_changes(
+            &mut command,
+            CommandChanges {
+                description: Some(String::new()),
+                timeout: Some("5m".to_string()),
+                allow_failure: Some(true),
+                files: Some(vec!["*.rs".to_string(), "*.toml".to_string()]),
+                env: vec![("NEW".to_string(), "2".to_string())],
+                unset_env: vec!["OLD".to_string()],
+                ..CommandChanges::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(command.command, "cargo test");
+        assert_eq!(command.description, None);
+        assert_eq!(command.timeout, Some("5m".parse().unwrap()));
+        assert!(command.allow_failure);
+        assert_eq!(command.files, vec!["*.rs", "*.toml"]);
+        assert_eq!(command.env.keys().collect::<Vec<_>>(), vec!["NEW"]);
+    }
+
+    #[test]
+    fn test_apply_changes_script_replaces_command() {
+        let mut command = Command::new("check".to_string(), "true".to_string(), None);
+
+        apply_changes(
+            &mut command,
+            CommandChanges {
+                script: Some("import sys\n".to_string()),
+                shell: Some("python3".to_string()),
+                ..CommandChanges::default()
+            },
+        )
+        .unwrap();
+
+        assert!(command.command.is_empty());
+        assert_eq!(command.body(), "import sys\n");
+        assert!(command.validate().is_ok());
+    }
+
+    #[test]
+    fn test_apply_changes_when() {
+        let mut command = Command::new("deploy".to_string(), "true".to_string(), None);
+
+        apply_changes(
+            &mut command,
+            CommandChanges {
+                when_branch: Some(vec!["main".to_string()]),
+                when_ci: Some("false".to_string()),
+                when_env: vec![("DEPLOY".to_string(), "1".to_string())],
+                ..CommandChanges::default()
+            },
+        )
+        .unwrap();
+
+        let when = command.when.clone().unwrap();
+        assert_eq!(when.branch, vec!["main"]);
+        assert_eq!(when.ci, Some(false));
+        assert_eq!(when.env.get("DEPLOY").map(String::as_str), Some("1"));
+
+        apply_changes(
+            &mut command,
+            CommandChanges {
+                when_branch: Some(vec![String::new()]),
+                when_ci: Some(String::new()),
+                unset_when_env: vec!["DEPLOY".to_string()],
+                ..CommandChanges::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(command.when, None);
+    }
+
+    #[test]
+    fn test_apply_changes_invalid_timeout() {
+        let mut command = Command::new("test".to_string(), "true".to_string(), None);
+
+        let err = apply_changes(
+            &mut command,
+            CommandChanges {
+                timeout: Some("soon".to_string()),
+                ..CommandChanges::default()
+            },
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().starts_with("Invalid timeout 'soon'"));
+    }
+
+    #[test]
+    fn test_replace_keeps_position_and_renames_dependencies() {
+        let hook = hook();
+        let mut command = hook.commands[0].clone();
+        command.id = "format".to_string();
+
+        let updated = replace(&hook, 0, command).unwrap();
+
+        let ids: Vec<&str> = updated.commands.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["format", "lint", "test"]);
+        assert_eq!(updated.commands[2].depends_on, vec!["format", "lint"]);
+    }
+
+    #[test]
+    fn test_replace_rejects_existing_id() {
+        let hook = hook();
+        let mut command = hook.commands[0].clone();
+        command.id = "lint".to_string();
+
+        let err = replace(&hook, 0, command).unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(HookmanError::CommandAlreadyExists(..))
+        ));
+    }
+
+    #[test]
+    fn test_replace_validates_hook() {
+        let hook = hook();
+        let mut command = hook.commands[0].clone();
+        command.depends_on = vec!["test".to_string()];
+
+        let err = replace(&hook, 0, command).unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(HookmanError::DependencyCycle(..))
+        ));
+    }
+}
@@ -1,12 +1,10 @@
-use crate::storage::{get_git_dir, Storage, TomlStorage};
+use crate::git::Repository;
+use crate::storage::{Storage, TomlStorage};
 use anyhow::Result;
 use colored::Colorize;
 
-pub fn execute() -> Result<()> {
-    // Check if we're in a git repository
-    get_git_dir()?;
-
-    let storage = TomlStorage::new();
+pub fn execute(repo: &Repository) -> Result<()> {
+    let storage = TomlStorage::new(repo.hookman_dir());
 
     if storage.is_initialized() {
         println!(
@@ -1,11 +1,13 @@
 use crate::error::HookmanError;
-use crate::models::HookType;
+use crate::git::Repository;
+use crate::models::{Command, Hook, HookType};
 use crate::storage::{Storage, TomlStorage};
 use anyhow::Result;
 use colored::Colorize;
+use std::collections::BTreeMap;
 
-pub fn execute(hook_type: Option<HookType>) -> Result<()> {
-    let storage = TomlStorage::new();
+pub fn execute(repo: &Repository, hook_type: Option<HookType>) -> Result<()> {
+    let storage = TomlStorage::new(repo.hookman_dir());
 
     if !storage.is_initialized() {
         return Err(HookmanError::NotInitialized.into());
@@ -21,19 +23,13 @@ fn list_specific_hook(storage: &TomlStorage, hook_type: HookType) -> Result<()>
     let hook = storage.load_hook(hook_type)?;
 
     println!("{}", format!("Hook: {}", hook_type).bold());
+    print_hook_settings(&hook);
 
     if hook.commands.is_empty() {
         println!("  No commands configured");
     } else {
         for command in &hook.commands {
-            println!(
-                "  {} {}",
-                format!("[{}]", command.id).cyan(),
-                command.command
-            );
-            if let Some(desc) = &command.description {
-                println!("      {}", desc.dimmed());
-            }
+            print_command(command);
         }
     }
 
@@ -58,18 +54,64 @@ fn list_all_hooks(storage: &TomlStorage) -> Result<()> {
             format!("{}", hook_type).green(),
             hook.commands.len()
         );
+        print_hook_settings(&hook);
 
         for command in &hook.commands {
-            println!(
-                "  {} {}",
-                format!("[{}]", command.id).cyan(),
-                command.command
-            );
-            if let Some(desc) = &command.description {
-                println!("      {}", desc.dimmed());
-            }
+            print_command(command);
         }
     }
 
     Ok(())
 }
+
+fn print_hook_settings(hook: &Hook) {
+    if let Some(mode) = hook.chain {
+        println!(
+            "  Runs the existing {} hook {} these commands",
+            hook.hook_type, mode
+        );
+    }
+    if let Some(shell) = &hook.shell {
+        println!("  Shell: {}", shell);
+    }
+    if !hook.env.is_empty() {
+        println!("  Environment: {}", format_env(&hook.env));
+    }
+}
+
+fn print_command(command: &Command) {
+    let id = format!("[{}]", command.id);
+    match (&command.script, command.enabled) {
+        (Some(script), true) => {
+            println!("  {} {}", id.cyan(), "script:".dimmed());
+            for line in script.lines() {
+                println!("      {}", line);
+            }
+        }
+        (Some(_), false) => println!("  {}", format!("{} script (disabled)", id).dimmed()),
+        (None, true) => println!("  {} {}", id.cyan(), command.command),
+        (None, false) => println!(
+            "  {}",
+            format!("{} {} (disabled)", id, command.command).dimmed()
+        ),
+    }
+    if let Some(desc) = &command.description {
+        println!("      {}", desc.dimmed());
+    }
+    if let Some(shell) = &command.shell {
+        println!("      {} {}", "shell:".dimmed(), shell);
+    }
+    if let Some(cwd) = &command.cwd {
+        println!("      {} {}", "in:".dimmed(), cwd.display());
+    }
+    if !command.env.is_empty() {
+        println!("      {} {}", "env:".dimmed(), format_env(&command.env));
+    }
+}
+
+fn format_env(env: &BTreeMap<String, String>) -> String {
+    env.iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
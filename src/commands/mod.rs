@@ -1,6 +1,13 @@
 pub mod add;
 pub mod apply;
+pub mod backups;
+pub mod edit;
 pub mod init;
 pub mod list;
 pub mod remove;
+pub mod reorder;
+pub mod run;
 pub mod status;
+pub mod toggle;
+pub mod transfer;
+pub mod uninstall;
@@ -1,11 +1,12 @@
 use crate::error::HookmanError;
+use crate::git::Repository;
 use crate::models::HookType;
 use crate::storage::{Storage, TomlStorage};
 use anyhow::Result;
 use colored::Colorize;
 
-pub fn execute(hook_type: HookType, command_id: String) -> Result<()> {
-    let storage = TomlStorage::new();
+pub fn execute(repo: &Repository, hook_type: HookType, command_id: String) -> Result<()> {
+    let storage = TomlStorage::new(repo.hookman_dir());
 
     if !storage.is_initialized() {
         return Err(HookmanError::NotInitialized.into());
@@ -13,6 +14,18 @@ pub fn execute(hook_type: HookType, command_id: String) -> Result<()> {
 
     let mut hook = storage.load_hook(hook_type)?;
 
+    let dependents: Vec<&str> = hook
+        .commands
+        .iter()
+        .filter(|c| c.depends_on.contains(&command_id))
+        .map(|c| c.id.as_str())
+        .collect();
+    if !dependents.is_empty() {
+        return Err(
+            HookmanError::CommandHasDependents(command_id.clone(), dependents.join(", ")).into(),
+        );
+    }
+
     let initial_len = hook.commands.len();
     hook.commands.retain(|c| c.id != command_id);
 
@@ -20,6 +33,7 @@ pub fn execute(hook_type: HookType, command_id: String) -> Result<()> {
         return Err(HookmanError::CommandNotFound(command_id, hook_type.to_string()).into());
     }
 
+    hook.validate()?;
     storage.save_hook(&hook)?;
 
     println!(
@@ -0,0 +1,169 @@
+use crate::cli::Placement;
+use crate::error::HookmanError;
+use crate::git::Repository;
+use crate::models::{Hook, HookType};
+use crate::storage::{Storage, TomlStorage};
+use anyhow::Result;
+use colored::Colorize;
+
+pub fn execute(
+    repo: &Repository,
+    hook_type: HookType,
+    command_id: String,
+    placement: Placement,
+) -> Result<()> {
+    let storage = TomlStorage::new(repo.hookman_dir());
+
+    if !storage.is_initialized() {
+        return Err(HookmanError::NotInitialized.into());
+    }
+
+    let mut hook = storage.load_hook(hook_type)?;
+    let to = move_command(&mut hook, &command_id, &placement)?;
+
+    hook.validate()?;
+    storage.save_hook(&hook)?;
+
+    println!(
+        "{}",
+        format!(
+            "✓ Moved command '{}' to position {} of {} in {} hook",
+            command_id,
+            to + 1,
+            hook.commands.len(),
+            hook_type
+        )
+        .green()
+    );
+
+    Ok(())
+}
+
+/// Moves the command `id` as `placement` says, returning its new index.
+fn move_command(hook: &mut Hook, id: &str, placement: &Placement) -> Result<usize, HookmanError> {
+    let from = find(hook, id)?;
+    let to = destination(hook, from, placement)?;
+
+    let command = hook.commands.remove(from);
+    hook.commands.insert(to, command);
+    Ok(to)
+}
+
+fn find(hook: &Hook, id: &str) -> Result<usize, HookmanError> {
+    hook.commands
+        .iter()
+        .position(|c| c.id == id)
+        .ok_or_else(|| HookmanError::CommandNotFound(id.to_string(), hook.hook_type.to_string()))
+}
+
+/// The index the command at `from` should have once moved, counted after it
+/// has been taken out of the list.
+fn destination(hook: &Hook, from: usize, placement: &Placement) -> Result<usize, HookmanError> {
+    if let Some(position) = placement.to {
+        let len = hook.commands.len();
+        if position == 0 || position > len {
+            return Err(HookmanError::InvalidPosition(
+                position,
+                hook.hook_type.to_string(),
+                len,
+            ));
+        }
+        return Ok(position - 1);
+    }
+
+    let (other, offset) = match (&placement.before, &placement.after) {
+        (Some(other), _) => (other, 0),
+        (_, Some(other)) => (other, 1),
+        // clap requires one of the options.
+        _ => return Ok(from),
+    };
+    let index = find(hook, other)?;
+    if index == from {
+        return Ok(from);
+    }
+    // Taking the command out shifts everything after it down by one.
+    let index = if index > from { index - 1 } else { index };
+    Ok(index + offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Command;
+
+    fn hook() -> Hook {
+        let mut hook = Hook::new(HookType::PreCommit);
+        for id in ["a", "b", "c", "d"] {
+            hook.commands
+                .push(Command::new(id.to_string(), "true".to_string(), None));
+        }
+        hook
+    }
+
+    fn moved(id: &str, placement: Placement) -> Result<Vec<String>, HookmanError> {
+        let mut hook = hook();
+        let to = move_command(&mut hook, id, &placement)?;
+        assert_eq!(hook.commands[to].id, id);
+        Ok(hook.commands.into_iter().map(|c| c.id).collect())
+    }
+
+    fn before(id: &str) -> Placement {
+        Placement {
+            before: Some(id.to_string()),
+            after: None,
+            to: None,
+        }
+    }
+
+    fn after(id: &str) -> Placement {
+        Placement {
+            before: None,
+            after: Some(id.to_string()),
+            to: None,
+        }
+    }
+
+    fn to(position: usize) -> Placement {
+        Placement {
+            before: None,
+            after: None,
+            to: Some(position),
+        }
+    }
+
+    #[test]
+    fn test_move_before() {
+        assert_eq!(moved("d", before("b")).unwrap(), ["a", "d", "b", "c"]);
+        assert_eq!(moved("a", before("c")).unwrap(), ["b", "a", "c", "d"]);
+        assert_eq!(moved("b", before("b")).unwrap(), ["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_move_after() {
+        assert_eq!(moved("a", after("c")).unwrap(), ["b", "c", "a", "d"]);
+        assert_eq!(moved("d", after("a")).unwrap(), ["a", "d", "b", "c"]);
+        assert_eq!(moved("b", after("d")).unwrap(), ["a", "c", "d", "b"]);
+    }
+
+    #[test]
+    fn test_move_to_position() {
+        assert_eq!(moved("c", to(1)).unwrap(), ["c", "a", "b", "d"]);
+        assert_eq!(moved("a", to(4)).unwrap(), ["b", "c", "d", "a"]);
+        assert!(matches!(
+            moved("a", to(0)),
+            Err(HookmanError::InvalidPosition(0, _, 4))
+        ));
+        assert!(matches!(
+            moved("a", to(5)),
+            Err(HookmanError::InvalidPosition(5, _, 4))
+        ));
+    }
+
+    #[test]
+    fn test_move_relative_to_unknown_command() {
+        assert!(matches!(
+            moved("a", before("z")),
+            Err(HookmanError::CommandNotFound(..))
+        ));
+    }
+}
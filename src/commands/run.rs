@@ -0,0 +1,51 @@
+use crate::git::Repository;
+use crate::models::HookType;
+use crate::runner::{HookContext, HookRunner};
+use crate::storage::{Storage, TomlStorage};
+use anyhow::Result;
+use colored::Colorize;
+
+pub fn execute(repo: &Repository, hook_type: HookType, args: Vec<String>) -> Result<()> {
+    let storage = TomlStorage::new(repo.hookman_dir());
+
+    // A hook can outlive the configuration, e.g. after checking out a branch
+    // that predates hookman. Don't block Git in that case.
+    if !storage.is_initialized() {
+        eprintln!(
+            "{}",
+            format!("hookman: not initialized, skipping {} hook", hook_type).yellow()
+        );
+        return Ok(());
+    }
+
+    if std::env::var_os("HOOKMAN").is_some_and(|value| value == "0") {
+        eprintln!(
+            "{}",
+            format!("hookman: HOOKMAN=0, skipping {} hook", hook_type).yellow()
+        );
+        return Ok(());
+    }
+
+    let mut hook = storage.load_hook(hook_type)?;
+    hook.validate()?;
+    hook.shell = hook.shell.or(storage.load_config()?.shell);
+    let context =
+        HookContext::new(hook_type, args, repo.work_tree().to_path_buf()).capture_stdin()?;
+
+    HookRunner::new(&context)
+        .with_chained(repo.chained_hooks_dir().join(hook_type.as_str()))
+        .with_stash_dir(repo.stash_dir())
+        .with_skipped(skipped_from_env())
+        .run(&hook)
+}
+
+/// Command IDs listed in `HOOKMAN_SKIP`, separated by commas.
+fn skipped_from_env() -> Vec<String> {
+    std::env::var("HOOKMAN_SKIP")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect()
+}
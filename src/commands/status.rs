@@ -1,28 +1,66 @@
 use crate::error::HookmanError;
+use crate::generator::{is_managed_file, ScriptGenerator, ScriptState};
+use crate::git::Repository;
 use crate::models::HookType;
-use crate::storage::{get_git_hooks_dir, get_hookman_dir, Storage, TomlStorage};
+use crate::storage::{Storage, TomlStorage};
 use anyhow::Result;
 use colored::Colorize;
 use std::fs;
 
-pub fn execute() -> Result<()> {
-    let storage = TomlStorage::new();
+pub fn execute(repo: &Repository, check: bool) -> Result<()> {
+    let storage = TomlStorage::new(repo.hookman_dir());
 
     if !storage.is_initialized() {
         return Err(HookmanError::NotInitialized.into());
     }
 
-    let hookman_dir = get_hookman_dir()?;
-    let git_hooks_dir = get_git_hooks_dir()?;
+    let default_hooks_dir = repo.default_hooks_dir();
+    let configured_hooks_path = repo.configured_hooks_path()?;
+    let git_hooks_dir = configured_hooks_path
+        .clone()
+        .unwrap_or_else(|| default_hooks_dir.clone());
 
     println!("{}", "Hookman Status".bold());
     println!("{}", "=============".bold());
     println!();
 
-    println!("Configuration directory: {}", hookman_dir.display());
-    println!("Git hooks directory: {}", git_hooks_dir.display());
+    println!("Repository: {}", repo.work_tree().display());
+    println!(
+        "Configuration directory: {}",
+        storage.hookman_dir().display()
+    );
+    if configured_hooks_path.is_some() {
+        println!(
+            "Git hooks directory: {} (from core.hooksPath)",
+            git_hooks_dir.display()
+        );
+    } else {
+        println!("Git hooks directory: {}", git_hooks_dir.display());
+    }
     println!();
 
+    if git_hooks_dir != default_hooks_dir {
+        let stranded = managed_hooks_in(&default_hooks_dir)?;
+        if !stranded.is_empty() {
+            println!(
+                "{}",
+                format!(
+                    "Warning: hookman hooks are installed in {} ({}), but core.hooksPath points Git to {}",
+                    default_hooks_dir.display(),
+                    stranded.join(", "),
+                    git_hooks_dir.display()
+                )
+                .yellow()
+            );
+            println!(
+                "{}",
+                "  Git will not run them. Use 'hookman apply' to install into the configured path"
+                    .yellow()
+            );
+            println!();
+        }
+    }
+
     let configured_hooks = storage.list_hooks()?;
 
     if configured_hooks.is_empty() {
@@ -32,6 +70,9 @@ pub fn execute() -> Result<()> {
 
     println!("{}", "Configured hooks:".bold());
 
+    let generator = ScriptGenerator::new(std::env::current_exe()?);
+    let mut drifted = 0;
+
     for hook_type in HookType::all() {
         let hook = storage.load_hook(hook_type)?;
         let git_hook_path = git_hooks_dir.join(hook_type.as_str());
@@ -42,14 +83,43 @@ pub fn execute() -> Result<()> {
 
         print!("  {} ", hook_type);
 
-        let status = if hook.commands.is_empty() {
+        let disabled = hook.commands.iter().filter(|c| !c.enabled).count();
+        let mut count = format!("{} commands", hook.commands.len() - disabled);
+        if disabled > 0 {
+            count.push_str(&format!(", {} disabled", disabled));
+        }
+        let status = if let Err(e) = hook.validate() {
+            drifted += 1;
+            format!("{}, invalid: {}", count, e).red()
+        } else if hook.commands.is_empty() {
             "no commands".yellow()
         } else if !git_hook_path.exists() {
-            format!("{} commands, not applied", hook.commands.len()).red()
-        } else if is_hookman_managed(&git_hook_path)? {
-            format!("{} commands, applied", hook.commands.len()).green()
+            drifted += 1;
+            format!("{}, not applied", count).red()
         } else {
-            format!("{} commands, external hook exists", hook.commands.len()).yellow()
+            let installed = String::from_utf8_lossy(&fs::read(&git_hook_path)?).into_owned();
+            // In chain mode the hook also needs the original preserved by apply.
+            let unchained = hook.chain.is_some()
+                && !repo.chained_hooks_dir().join(hook_type.as_str()).is_file();
+            match generator.check(&hook, &installed)? {
+                ScriptState::Applied if unchained => {
+                    drifted += 1;
+                    format!("{}, outdated (no hook to chain)", count).yellow()
+                }
+                ScriptState::Applied => format!("{}, applied", count).green(),
+                ScriptState::Outdated => {
+                    drifted += 1;
+                    format!("{}, outdated", count).yellow()
+                }
+                ScriptState::Modified => {
+                    drifted += 1;
+                    format!("{}, modified by hand", count).red()
+                }
+                ScriptState::External => {
+                    drifted += 1;
+                    format!("{}, external hook exists", count).yellow()
+                }
+            }
         };
 
         println!("- {}", status);
@@ -58,6 +128,10 @@ pub fn execute() -> Result<()> {
     println!();
     println!("Use 'hookman apply' to apply pending changes");
 
+    if check && drifted > 0 {
+        return Err(HookmanError::HooksOutOfSync(drifted).into());
+    }
+
     Ok(())
 }
 
@@ -66,6 +140,17 @@ fn is_hookman_managed(path: &std::path::Path) -> Result<bool> {
         return Ok(false);
     }
 
-    let content = fs::read_to_string(path)?;
-    Ok(content.contains("Generated by hookman"))
+    is_managed_file(path)
+}
+
+fn managed_hooks_in(dir: &std::path::Path) -> Result<Vec<String>> {
+    let mut hooks = Vec::new();
+
+    for hook_type in HookType::all() {
+        if is_hookman_managed(&dir.join(hook_type.as_str()))? {
+            hooks.push(hook_type.to_string());
+        }
+    }
+
+    Ok(hooks)
 }
@@ -0,0 +1,57 @@
+use crate::error::HookmanError;
+use crate::git::Repository;
+use crate::models::HookType;
+use crate::storage::{Storage, TomlStorage};
+use anyhow::Result;
+use colored::Colorize;
+
+pub fn enable(repo: &Repository, hook_type: HookType, command_id: String) -> Result<()> {
+    set_enabled(repo, hook_type, command_id, true)
+}
+
+pub fn disable(repo: &Repository, hook_type: HookType, command_id: String) -> Result<()> {
+    set_enabled(repo, hook_type, command_id, false)
+}
+
+fn set_enabled(
+    repo: &Repository,
+    hook_type: HookType,
+    command_id: String,
+    enabled: bool,
+) -> Result<()> {
+    let storage = TomlStorage::new(repo.hookman_dir());
+
+    if !storage.is_initialized() {
+        return Err(HookmanError::NotInitialized.into());
+    }
+
+    let mut hook = storage.load_hook(hook_type)?;
+    let (state, done) = if enabled {
+        ("enabled", "Enabled")
+    } else {
+        ("disabled", "Disabled")
+    };
+
+    let Some(command) = hook.commands.iter_mut().find(|c| c.id == command_id) else {
+        return Err(HookmanError::CommandNotFound(command_id, hook_type.to_string()).into());
+    };
+
+    if command.enabled == enabled {
+        println!(
+            "Command '{}' in {} hook is already {}",
+            command_id, hook_type, state
+        );
+        return Ok(());
+    }
+
+    command.enabled = enabled;
+    hook.validate()?;
+    storage.save_hook(&hook)?;
+
+    println!(
+        "{}",
+        format!("✓ {} command '{}' in {} hook", done, command_id, hook_type).green()
+    );
+
+    Ok(())
+}
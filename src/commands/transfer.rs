@@ -0,0 +1,209 @@
+use crate::error::HookmanError;
+use crate::git::Repository;
+use crate::models::{Command, Hook, HookType};
+use crate::storage::{Storage, TomlStorage};
+use anyhow::Result;
+use colored::Colorize;
+
+/// Copies a command, with all its fields, to the end of another hook.
+pub fn cp(
+    repo: &Repository,
+    from: HookType,
+    command_id: String,
+    to: HookType,
+    new_id: Option<String>,
+) -> Result<()> {
+    let storage = storage(repo)?;
+    let source = storage.load_hook(from)?;
+    let command = find(&source, &command_id)?.clone();
+
+    // Copying within a hook adds to the hook just loaded.
+    let target = if to == from {
+        source
+    } else {
+        storage.load_hook(to)?
+    };
+    let (target, id) = with_command(target, command, new_id)?;
+    storage.save_hook(&target)?;
+
+    println!(
+        "{}",
+        format!(
+            "✓ Copied command '{}' from {} to {} hook",
+            command_id, from, to
+        )
+        .green()
+    );
+    print_new_id(&command_id, &id);
+
+    Ok(())
+}
+
+/// Moves a command, with all its fields, to the end of another hook.
+pub fn mv(
+    repo: &Repository,
+    from: HookType,
+    command_id: String,
+    to: HookType,
+    new_id: Option<String>,
+) -> Result<()> {
+    if to == from {
+        return Err(HookmanError::SameHook(command_id, from.to_string()).into());
+    }
+
+    let storage = storage(repo)?;
+    let mut source = storage.load_hook(from)?;
+    let command = find(&source, &command_id)?.clone();
+
+    let dependents: Vec<&str> = source
+        .commands
+        .iter()
+        .filter(|c| c.depends_on.contains(&command_id))
+        .map(|c| c.id.as_str())
+        .collect();
+    if !dependents.is_empty() {
+        return Err(HookmanError::MovedCommandHasDependents(
+            command_id.clone(),
+            dependents.join(", "),
+        )
+        .into());
+    }
+
+    let (target, id) = with_command(storage.load_hook(to)?, command, new_id)?;
+    source.commands.retain(|c| c.id != command_id);
+    source.validate()?;
+
+    // Write the target first: if saving the source then fails, the command
+    // exists twice rather than not at all.
+    storage.save_hook(&target)?;
+    storage.save_hook(&source)?;
+
+    println!(
+        "{}",
+        format!(
+            "✓ Moved command '{}' from {} to {} hook",
+            command_id, from, to
+        )
+        .green()
+    );
+    print_new_id(&command_id, &id);
+
+    Ok(())
+}
+
+fn storage(repo: &Repository) -> Result<TomlStorage> {
+    let storage = TomlStorage::new(repo.hookman_dir());
+
+    if !storage.is_initialized() {
+        return Err(HookmanError::NotInitialized.into());
+    }
+
+    Ok(storage)
+}
+
+fn find<'a>(hook: &'a Hook, id: &str) -> Result<&'a Command, HookmanError> {
+    hook.commands
+        .iter()
+        .find(|c| c.id == id)
+        .ok_or_else(|| HookmanError::CommandNotFound(id.to_string(), hook.hook_type.to_string()))
+}
+
+/// Appends `command` to `target`, renamed to `new_id` if given, and checks
+/// the result. Returns the hook and the command's ID in it.
+fn with_command(
+    mut target: Hook,
+    mut command: Command,
+    new_id: Option<String>,
+) -> Result<(Hook, String)> {
+    if let Some(new_id) = new_id {
+        Command::validate_id(&new_id)?;
+        command.id = new_id;
+    }
+    if target.commands.iter().any(|c| c.id == command.id) {
+        return Err(
+            HookmanError::CommandAlreadyExists(command.id, target.hook_type.to_string()).into(),
+        );
+    }
+
+    let id = command.id.clone();
+    target.commands.push(command);
+    // The command's depends_on must name commands of the target hook too.
+    target.validate()?;
+    Ok((target, id))
+}
+
+fn print_new_id(old_id: &str, new_id: &str) {
+    if old_id != new_id {
+        println!("  New ID: {}", new_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(hook_type: HookType, ids: &[&str]) -> Hook {
+        let mut hook = Hook::new(hook_type);
+        for id in ids {
+            hook.commands
+                .push(Command::new(id.to_string(), "true".to_string(), None));
+        }
+        hook
+    }
+
+    #[test]
+    fn test_with_command_keeps_fields() {
+        let mut command = Command::new("lint".to_string(), "make lint".to_string(), None);
+        command.timeout = Some("2m".parse().unwrap());
+        command.files = vec!["*.rs".to_string()];
+        command.enabled = false;
+
+        let (target, id) =
+            with_command(hook(HookType::PrePush, &["test"]), command.clone(), None).unwrap();
+
+        assert_eq!(id, "lint");
+        assert_eq!(target.commands[1], command);
+    }
+
+    #[test]
+    fn test_with_command_conflict_and_new_id() {
+        let target = hook(HookType::PrePush, &["lint"]);
+        let command = Command::new("lint".to_string(), "make lint".to_string(), None);
+
+        let err = with_command(target.clone(), command.clone(), None).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(HookmanError::CommandAlreadyExists(..))
+        ));
+
+        let (target, id) =
+            with_command(target, command.clone(), Some("lint-all".to_string())).unwrap();
+        assert_eq!(id, "lint-all");
+        assert_eq!(target.commands[1].command, "make lint");
+
+        let err = with_command(
+            hook(HookType::PrePush, &[]),
+            command,
+            Some("bad id".to_string()),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(HookmanError::InvalidCommand(..))
+        ));
+    }
+
+    #[test]
+    fn test_with_command_checks_dependencies_in_target() {
+        let mut command = Command::new("test".to_string(), "make test".to_string(), None);
+        command.depends_on = vec!["build".to_string()];
+
+        let err = with_command(hook(HookType::PrePush, &[]), command.clone(), None).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(HookmanError::UnknownDependency(..))
+        ));
+
+        assert!(with_command(hook(HookType::PrePush, &["build"]), command, None).is_ok());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::generator::is_managed_file;
+use crate::git::Repository;
+use crate::models::HookType;
+use crate::storage::BackupStore;
+use anyhow::Result;
+use colored::Colorize;
+use std::fs;
+
+pub fn execute(repo: &Repository, hook_types: Vec<HookType>, dry_run: bool) -> Result<()> {
+    let git_hooks_dir = repo.git_hooks_dir()?;
+    let backups = BackupStore::new(repo.backups_dir());
+    let explicit = !hook_types.is_empty();
+    let hook_types = if explicit {
+        hook_types
+    } else {
+        HookType::all()
+    };
+
+    if dry_run {
+        println!("{}", "DRY RUN - No changes will be made".yellow().bold());
+        println!();
+    }
+
+    let mut removed = 0;
+
+    for hook_type in hook_types {
+        let hook_path = git_hooks_dir.join(hook_type.as_str());
+
+        if !hook_path.exists() {
+            if explicit {
+                println!("{}", format!("  No {} hook installed", hook_type).dimmed());
+            }
+            continue;
+        }
+
+        if !is_managed_file(&hook_path)? {
+            if explicit {
+                println!(
+                    "{}",
+                    format!("  Skipping {}: not managed by hookman", hook_type).yellow()
+                );
+            }
+            continue;
+        }
+
+        removed += 1;
+        let legacy_path = hook_path.with_extension("backup");
+        let legacy = legacy_path.exists() && !is_managed_file(&legacy_path)?;
+        let restore_from = if legacy {
+            Some(legacy_path.clone())
+        } else {
+            backups.latest(hook_type)?.map(|backup| backup.path)
+        };
+
+        if dry_run {
+            println!(
+                "{}",
+                format!("Would remove: {}", hook_path.display()).blue()
+            );
+            if let Some(source) = &restore_from {
+                println!("{}", format!("Would restore: {}", source.display()).blue());
+            }
+            continue;
+        }
+
+        fs::remove_file(&hook_path)?;
+
+        // `.backup` files from older hookman versions are consumed on restore;
+        // ones holding a hookman script are leftovers and are just dropped.
+        match &restore_from {
+            Some(source) if legacy => fs::rename(source, &hook_path)?,
+            Some(source) => {
+                fs::copy(source, &hook_path)?;
+            }
+            None => {}
+        }
+        if legacy_path.exists() {
+            fs::remove_file(&legacy_path)?;
+        }
+        let chained_path = repo.chained_hooks_dir().join(hook_type.as_str());
+        if chained_path.exists() {
+            fs::remove_file(&chained_path)?;
+        }
+
+        match restore_from {
+            Some(source) => println!(
+                "{}",
+                format!(
+                    "✓ Removed {} hook and restored {}",
+                    hook_type,
+                    source.display()
+                )
+                .green()
+            ),
+            None => println!("{}", format!("✓ Removed {} hook", hook_type).green()),
+        }
+    }
+
+    if removed == 0 {
+        println!("{}", "No hookman-managed hooks to uninstall".yellow());
+    } else if !dry_run {
+        println!();
+        println!("{}", "Hooks uninstalled successfully!".green().bold());
+    }
+
+    Ok(())
+}
@@ -6,6 +6,12 @@ pub enum HookmanError {
     #[error("Not in a Git repository")]
     NotInGitRepo,
 
+    #[error("Repository path '{0}' does not exist or is not a directory")]
+    InvalidRepoPath(String),
+
+    #[error("Invalid git directory: {0}")]
+    InvalidGitDir(String),
+
     #[error("Hookman not initialized. Run 'hookman init' first")]
     NotInitialized,
 
@@ -18,12 +24,59 @@ pub enum HookmanError {
     #[error("Command with ID '{0}' already exists in hook '{1}'")]
     CommandAlreadyExists(String, String),
 
+    #[error("Command '{0}' in hook '{2}' depends on unknown command '{1}'")]
+    UnknownDependency(String, String, String),
+
+    #[error("Dependency cycle in hook '{1}': {0}")]
+    DependencyCycle(String, String),
+
+    #[error("Command '{0}' can't be removed, it is required by: {1}")]
+    CommandHasDependents(String, String),
+
+    #[error("Command '{0}' can't be moved while other commands depend on it: {1}")]
+    MovedCommandHasDependents(String, String),
+
+    #[error("Command '{0}' is already in the {1} hook; use 'hookman edit --new-id' to rename it")]
+    SameHook(String, String),
+
+    #[error("Invalid file pattern '{0}': {1}")]
+    InvalidPattern(String, String),
+
+    #[error("Position {0} is out of range for hook '{1}', use 1 to {2}")]
+    InvalidPosition(usize, String, usize),
+
+    #[error("Invalid command '{0}': {1}")]
+    InvalidCommand(String, String),
+
+    #[error(
+        "Working directory '{0}' of command '{1}' must be a relative path inside the repository"
+    )]
+    InvalidCwd(String, String),
+
+    #[error("No backup '{1}' found for hook '{0}'")]
+    BackupNotFound(String, String),
+
     #[error("Failed to read configuration: {0}")]
     ConfigReadError(String),
 
     #[error("Failed to write configuration: {0}")]
     ConfigWriteError(String),
 
+    #[error("Command '{0}' failed ({1})")]
+    CommandFailed(String, String),
+
+    #[error("Commands failed: {0}")]
+    CommandsFailed(String),
+
+    #[error("Commands changed files with unstaged changes: {0}. The unstaged changes were kept and the commands' changes discarded")]
+    StashConflict(String),
+
+    #[error("Interrupted")]
+    Interrupted,
+
+    #[error("{0} hook(s) invalid or out of sync with the configuration")]
+    HooksOutOfSync(usize),
+
     #[error("Failed to generate hook script: {0}")]
     ScriptGenerationError(String),
 
@@ -1,3 +1,5 @@
+pub mod quote;
 mod script;
 
-pub use script::ScriptGenerator;
+pub use quote::shell_quote;
+pub use script::{is_managed_file, ScriptGenerator, ScriptState};
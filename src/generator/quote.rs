@@ -0,0 +1,118 @@
+//! Quoting for text that ends up in shell code: the installed hook scripts
+//! and the `{files}` substituted into commands.
+//!
+//! Everything user-supplied goes through [`shell_quote`]; nothing is pasted
+//! into a script as is.
+
+/// Quotes a string as a single word for a POSIX shell. Nothing between
+/// single quotes is special, so only the single quote itself needs care: it
+/// closes the quotes, is escaped, and reopens them.
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Reads back a word written by [`shell_quote`] from the start of `text`,
+/// ignoring anything after it. Returns `None` if `text` doesn't start with
+/// one.
+pub fn shell_unquote(text: &str) -> Option<String> {
+    let mut value = String::new();
+    let mut rest = text.strip_prefix('\'')?;
+    loop {
+        let end = rest.find('\'')?;
+        value.push_str(&rest[..end]);
+        rest = &rest[end + 1..];
+        match rest.strip_prefix("\\''") {
+            Some(after) => {
+                value.push('\'');
+                rest = after;
+            }
+            None => return Some(value),
+        }
+    }
+}
+
+/// Strings built from fragments that break naive quoting, mixed with random
+/// characters. Deterministic, so a failure can be reproduced.
+#[cfg(test)]
+pub fn hostile_strings(count: usize) -> Vec<String> {
+    const FRAGMENTS: &[&str] = &[
+        "'", "\"", "`", "$", "$(id)", "${HOME}", "`id`", "\\", "\\'", "'\\''", "\n", "\t", " ",
+        ";", "&&", "|", ">", "<", "*", "?", "[a]", "~", "#", "!", "-n", "--", "{files}", "%s", "é",
+        "日本", "\r", "\u{7f}",
+    ];
+
+    let mut state: u64 = 0x2545f4914f6cdd1d;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    let mut strings: Vec<String> = FRAGMENTS.iter().map(|f| f.to_string()).collect();
+    strings.push(String::new());
+    while strings.len() < count {
+        let mut value = String::new();
+        for _ in 0..next() % 8 + 1 {
+            if next() % 3 == 0 {
+                // Any character but NUL, which can't appear in an argument.
+                let c = char::from_u32((next() % 0x2ff) as u32 + 1).unwrap_or('x');
+                value.push(c);
+            } else {
+                value.push_str(FRAGMENTS[(next() % FRAGMENTS.len() as u64) as usize]);
+            }
+        }
+        strings.push(value);
+    }
+    strings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("plain"), "'plain'");
+        assert_eq!(shell_quote("a'b"), "'a'\\''b'");
+        assert_eq!(shell_quote("$(rm -rf /)"), "'$(rm -rf /)'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn test_shell_unquote() {
+        assert_eq!(shell_unquote("'a'\\''b'\nnext").as_deref(), Some("a'b"));
+        assert_eq!(shell_unquote("plain"), None);
+        assert_eq!(shell_unquote("'unterminated"), None);
+
+        for value in hostile_strings(300) {
+            assert_eq!(shell_unquote(&shell_quote(&value)), Some(value));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shell_quote_round_trips_hostile_strings() {
+        let strings = hostile_strings(300);
+        // One shell for all of them: print each quoted word followed by a
+        // separator that can't occur in the input.
+        let script: String = strings
+            .iter()
+            .map(|value| format!("printf '%s\\000' {}\n", shell_quote(value)))
+            .collect();
+
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(&script)
+            .output()
+            .unwrap();
+
+        assert!(output.status.success(), "{:?}", output);
+        let printed: Vec<String> = output
+            .stdout
+            .split(|&byte| byte == 0)
+            .map(|word| String::from_utf8(word.to_vec()).unwrap())
+            .collect();
+        assert_eq!(printed[..strings.len()], strings[..]);
+    }
+}
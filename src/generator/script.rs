@@ -1,104 +1,340 @@
+use crate::generator::quote::{shell_quote, shell_unquote};
 use crate::models::Hook;
 #[cfg(test)]
 use crate::models::{Command, HookType};
 use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
 
-pub struct ScriptGenerator;
+const MANAGED_MARKER: &str = "# Generated by hookman";
+const SCRIPT_HASH_PREFIX: &str = "# hookman-script-hash: ";
+const EXECUTABLE_PREFIX: &str = "hookman_bin=";
+
+/// How an installed hook script relates to the current configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptState {
+    /// Identical to what `apply` would write now.
+    Applied,
+    /// Written by hookman, but by an older version or for another hook type.
+    Outdated,
+    /// Written by hookman and edited afterwards.
+    Modified,
+    /// Not written by hookman.
+    External,
+}
+
+/// Generates the hook scripts installed into the Git hooks directory.
+///
+/// The scripts are thin shims that hand control to `hookman run`, so changes
+/// to `.hookman/hooks/*.toml` take effect without re-applying.
+pub struct ScriptGenerator {
+    executable: PathBuf,
+}
 
 impl ScriptGenerator {
-    pub fn new() -> Self {
-        ScriptGenerator
+    pub fn new(executable: PathBuf) -> Self {
+        ScriptGenerator { executable }
     }
 
     pub fn generate(&self, hook: &Hook) -> Result<String> {
         let mut script = String::new();
 
         script.push_str("#!/bin/sh\n");
-        script.push_str("# Generated by hookman\n");
+        script.push_str(MANAGED_MARKER);
+        script.push('\n');
         script.push_str("# DO NOT EDIT - This file is managed by hookman\n");
+        script.push_str(&format!(
+            "# Commands are loaded from .hookman/hooks/{}.toml when the hook runs\n",
+            hook.hook_type
+        ));
         script.push('\n');
-        script.push_str("set -e  # Exit on first error\n");
+
+        // HOOKMAN=0 turns every hook off, even without hookman installed.
+        script.push_str("if [ \"$HOOKMAN\" = 0 ]; then\n");
+        script.push_str("    exit 0\n");
+        script.push_str("fi\n");
         script.push('\n');
 
-        if hook.commands.is_empty() {
-            script.push_str("# No commands configured for this hook\n");
-            return Ok(script);
+        // Prefer the binary that applied the hook, falling back to PATH so the
+        // hook survives hookman being reinstalled elsewhere.
+        script.push_str(&format!(
+            "{}{}\n",
+            EXECUTABLE_PREFIX,
+            shell_quote(&self.executable.to_string_lossy())
+        ));
+        script.push_str("if [ ! -x \"$hookman_bin\" ]; then\n");
+        script.push_str("    hookman_bin=hookman\n");
+        script.push_str("fi\n");
+        script.push_str("if ! command -v \"$hookman_bin\" >/dev/null 2>&1; then\n");
+        script.push_str(
+            "    echo \"hookman: executable not found, install it or run 'hookman apply' again\" >&2\n",
+        );
+        script.push_str("    exit 1\n");
+        script.push_str("fi\n");
+        script.push('\n');
+
+        script.push_str(&format!(
+            "exec \"$hookman_bin\" run {} -- \"$@\"\n",
+            hook.hook_type
+        ));
+
+        // The script hash covers everything else in the file, so hand edits
+        // can be told apart from a stale configuration.
+        let script_hash = fingerprint(&script);
+        let insert_at = script.find("\n\n").map_or(script.len(), |index| index + 1);
+        script.insert_str(
+            insert_at,
+            &format!("{}{}\n", SCRIPT_HASH_PREFIX, script_hash),
+        );
+
+        Ok(script)
+    }
+
+    /// Compares an installed hook script against what would be generated for
+    /// `hook` now. The shim doesn't depend on the hook's commands, so only its
+    /// format and hook type matter, and it is regenerated with the executable
+    /// path it records rather than the one running now.
+    pub fn check(&self, hook: &Hook, installed: &str) -> Result<ScriptState> {
+        if !is_managed(installed) {
+            return Ok(ScriptState::External);
         }
 
-        script.push_str(&format!("echo \"Running {} hooks...\"\n", hook.hook_type));
-        script.push('\n');
+        let expected = match recorded_executable(installed) {
+            Some(executable) => ScriptGenerator::new(executable).generate(hook)?,
+            None => self.generate(hook)?,
+        };
+        if installed == expected {
+            return Ok(ScriptState::Applied);
+        }
+
+        // Scripts from hookman versions without hashes can only be outdated.
+        let Some(recorded) = installed
+            .lines()
+            .find_map(|line| line.strip_prefix(SCRIPT_HASH_PREFIX))
+        else {
+            return Ok(ScriptState::Outdated);
+        };
+
+        let without_hash: String = installed
+            .split_inclusive('\n')
+            .filter(|line| !line.starts_with(SCRIPT_HASH_PREFIX))
+            .collect();
 
-        for command in &hook.commands {
-            if let Some(desc) = &command.description {
-                script.push_str(&format!("# {}: {}\n", command.id, desc));
-                script.push_str(&format!("echo \"  → {}: {}\"\n", command.id, desc));
-            } else {
-                script.push_str(&format!("# {}\n", command.id));
-                script.push_str(&format!("echo \"  → {}\"\n", command.id));
-            }
-            script.push_str(&format!("{}\n", command.command));
-            script.push('\n');
+        if fingerprint(&without_hash) == recorded.trim() {
+            Ok(ScriptState::Outdated)
+        } else {
+            Ok(ScriptState::Modified)
         }
+    }
+}
+
+/// Whether a hook script was written by hookman.
+pub fn is_managed(script: &str) -> bool {
+    script.contains(MANAGED_MARKER)
+}
 
-        script.push_str(&format!("echo \"All {} hooks passed!\"\n", hook.hook_type));
+/// Like [`is_managed`], for a file that may not be valid UTF-8 (hooks can be
+/// compiled binaries).
+pub fn is_managed_file(path: &Path) -> Result<bool> {
+    let contents = fs::read(path)?;
+    Ok(is_managed(&String::from_utf8_lossy(&contents)))
+}
 
-        Ok(script)
+/// The executable path a shim was generated with, from its `hookman_bin=`
+/// line. The quoted path may itself contain newlines.
+fn recorded_executable(script: &str) -> Option<PathBuf> {
+    let start = script.find(&format!("\n{}", EXECUTABLE_PREFIX))? + 1 + EXECUTABLE_PREFIX.len();
+    shell_unquote(&script[start..]).map(PathBuf::from)
+}
+
+/// A stable 64-bit FNV-1a hash, rendered as hex. `DefaultHasher` is not
+/// guaranteed to be stable across Rust releases, so it can't be persisted.
+fn fingerprint(text: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in text.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
     }
+    format!("{:016x}", hash)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn generator() -> ScriptGenerator {
+        ScriptGenerator::new(PathBuf::from("/usr/local/bin/hookman"))
+    }
+
     #[test]
-    fn test_generate_empty_hook() {
-        let generator = ScriptGenerator::new();
+    fn test_generate_shim() {
         let hook = Hook::new(HookType::PreCommit);
 
-        let script = generator.generate(&hook).unwrap();
+        let script = generator().generate(&hook).unwrap();
 
-        assert!(script.contains("#!/bin/sh"));
+        assert!(script.starts_with("#!/bin/sh\n"));
         assert!(script.contains("# Generated by hookman"));
-        assert!(script.contains("# No commands configured for this hook"));
+        assert!(script.contains("hookman_bin='/usr/local/bin/hookman'"));
+        assert!(script.contains("exec \"$hookman_bin\" run pre-commit -- \"$@\""));
     }
 
     #[test]
-    fn test_generate_hook_with_commands() {
-        let generator = ScriptGenerator::new();
+    fn test_generate_uses_hook_type() {
+        let hook = Hook::new(HookType::PrePush);
+
+        let script = generator().generate(&hook).unwrap();
+
+        assert!(script.contains(".hookman/hooks/pre-push.toml"));
+        assert!(script.contains("run pre-push --"));
+    }
+
+    #[test]
+    fn test_generate_quotes_executable_path() {
+        let generator = ScriptGenerator::new(PathBuf::from("/opt/it's here/hookman"));
+        let hook = Hook::new(HookType::PreCommit);
+
+        let script = generator.generate(&hook).unwrap();
+
+        assert!(script.contains("hookman_bin='/opt/it'\\''s here/hookman'"));
+    }
+
+    #[test]
+    fn test_generate_embeds_hashes() {
+        let hook = Hook::new(HookType::PreCommit);
+
+        let script = generator().generate(&hook).unwrap();
+
+        assert!(script.contains(SCRIPT_HASH_PREFIX));
+    }
+
+    #[test]
+    fn test_generate_ignores_commands() {
         let mut hook = Hook::new(HookType::PreCommit);
+        let before = generator().generate(&hook).unwrap();
 
         hook.commands.push(Command::new(
-            "format".to_string(),
-            "cargo fmt -- --check".to_string(),
-            Some("Check formatting".to_string()),
+            "test".to_string(),
+            "cargo test".to_string(),
+            None,
         ));
 
+        assert_eq!(before, generator().generate(&hook).unwrap());
+    }
+
+    #[test]
+    fn test_check_applied() {
+        let hook = Hook::new(HookType::PreCommit);
+        let script = generator().generate(&hook).unwrap();
+
+        let state = generator().check(&hook, &script).unwrap();
+
+        assert_eq!(state, ScriptState::Applied);
+    }
+
+    #[test]
+    fn test_check_applied_after_config_change() {
+        let mut hook = Hook::new(HookType::PreCommit);
+        let script = generator().generate(&hook).unwrap();
         hook.commands.push(Command::new(
-            "lint".to_string(),
-            "cargo clippy".to_string(),
+            "test".to_string(),
+            "cargo test".to_string(),
             None,
         ));
 
-        let script = generator.generate(&hook).unwrap();
+        let state = generator().check(&hook, &script).unwrap();
 
-        assert!(script.contains("#!/bin/sh"));
-        assert!(script.contains("Running pre-commit hooks..."));
-        assert!(script.contains("# format: Check formatting"));
-        assert!(script.contains("echo \"  → format: Check formatting\""));
-        assert!(script.contains("cargo fmt -- --check"));
-        assert!(script.contains("# lint"));
-        assert!(script.contains("echo \"  → lint\""));
-        assert!(script.contains("cargo clippy"));
-        assert!(script.contains("All pre-commit hooks passed!"));
+        assert_eq!(state, ScriptState::Applied);
     }
 
     #[test]
-    fn test_script_has_error_handling() {
-        let generator = ScriptGenerator::new();
-        let hook = Hook::new(HookType::PrePush);
+    fn test_check_uses_recorded_executable() {
+        let hook = Hook::new(HookType::PreCommit);
+        let script = ScriptGenerator::new(PathBuf::from("/opt/it's\nhere/hookman"))
+            .generate(&hook)
+            .unwrap();
 
-        let script = generator.generate(&hook).unwrap();
+        let state = generator().check(&hook, &script).unwrap();
+
+        assert_eq!(state, ScriptState::Applied);
+    }
+
+    #[test]
+    fn test_check_outdated() {
+        let hook = Hook::new(HookType::PreCommit);
+        let script = generator().generate(&Hook::new(HookType::PrePush)).unwrap();
+
+        let state = generator().check(&hook, &script).unwrap();
+
+        assert_eq!(state, ScriptState::Outdated);
+    }
+
+    #[test]
+    fn test_check_modified() {
+        let hook = Hook::new(HookType::PreCommit);
+        let script = generator()
+            .generate(&hook)
+            .unwrap()
+            .replace("exec \"$hookman_bin\"", "echo hi\nexec \"$hookman_bin\"");
 
-        assert!(script.contains("set -e  # Exit on first error"));
+        let state = generator().check(&hook, &script).unwrap();
+
+        assert_eq!(state, ScriptState::Modified);
+    }
+
+    #[test]
+    fn test_check_legacy_script_is_outdated() {
+        let hook = Hook::new(HookType::PreCommit);
+        let script = "#!/bin/sh\n# Generated by hookman\nset -e\ncargo test\n";
+
+        let state = generator().check(&hook, script).unwrap();
+
+        assert_eq!(state, ScriptState::Outdated);
+    }
+
+    #[test]
+    fn test_check_external() {
+        let hook = Hook::new(HookType::PreCommit);
+
+        let state = generator().check(&hook, "#!/bin/sh\nexit 0\n").unwrap();
+
+        assert_eq!(state, ScriptState::External);
+    }
+
+    #[test]
+    fn test_fingerprint_is_stable() {
+        assert_eq!(fingerprint(""), "cbf29ce484222325");
+        assert_eq!(fingerprint("a"), "af63dc4c8601ec8c");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_generate_is_valid_shell_for_hostile_executable_path() {
+        use crate::generator::quote::hostile_strings;
+
+        // The executable path is the only outside value in the shim.
+        let hook = Hook::new(HookType::PreCommit);
+        for value in hostile_strings(100) {
+            let generator = ScriptGenerator::new(PathBuf::from(format!("/opt/{}/hookman", value)));
+
+            let script = generator.generate(&hook).unwrap();
+
+            let syntax = std::process::Command::new("sh")
+                .args(["-n", "-c", &script])
+                .output()
+                .unwrap();
+            assert!(syntax.status.success(), "{:?}\n{}", value, script);
+
+            // The executable path must come out of the script unchanged.
+            let assignment = &script[..script.find("if [ ! -x").unwrap()];
+            let output = std::process::Command::new("sh")
+                .arg("-c")
+                .arg(format!("{}\nprintf %s \"$hookman_bin\"", assignment))
+                .output()
+                .unwrap();
+            assert_eq!(
+                String::from_utf8(output.stdout).unwrap(),
+                format!("/opt/{}/hookman", value)
+            );
+        }
     }
 }
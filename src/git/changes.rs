@@ -0,0 +1,140 @@
+use anyhow::{bail, Result};
+use std::path::Path;
+
+/// Paths staged for the next commit, leaving out deleted files.
+pub fn staged_files(work_tree: &Path) -> Result<Vec<String>> {
+    git_paths(
+        work_tree,
+        &[
+            "diff",
+            "--cached",
+            "--name-only",
+            "-z",
+            "--diff-filter=ACMR",
+        ],
+    )
+}
+
+/// Paths whose work tree contents differ from the index.
+pub fn unstaged_files(work_tree: &Path) -> Result<Vec<String>> {
+    git_paths(work_tree, &["diff", "--name-only", "-z", "--no-renames"])
+}
+
+/// Adds the given paths to the index.
+pub fn stage(work_tree: &Path, paths: &[String]) -> Result<()> {
+    let status = std::process::Command::new("git")
+        .current_dir(work_tree)
+        .args(["add", "--"])
+        .args(paths)
+        .status()?;
+
+    if !status.success() {
+        bail!("git add failed");
+    }
+    Ok(())
+}
+
+/// Paths changed by the commits a pre-push hook is about to push, leaving out
+/// deleted files. `refs` is the hook's stdin: one
+/// `<local ref> <local sha> <remote ref> <remote sha>` line per pushed ref.
+pub fn pushed_files(work_tree: &Path, remote: &str, refs: &str) -> Result<Vec<String>> {
+    let mut files: Vec<String> = Vec::new();
+
+    for line in refs.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [_, local_sha, _, remote_sha] = fields[..] else {
+            continue;
+        };
+
+        // Deleting a remote ref pushes no content.
+        if is_null(local_sha) {
+            continue;
+        }
+
+        let changed = if !is_null(remote_sha) && object_exists(work_tree, remote_sha) {
+            git_paths(
+                work_tree,
+                &[
+                    "diff",
+                    "--name-only",
+                    "-z",
+                    "--diff-filter=ACMR",
+                    remote_sha,
+                    local_sha,
+                ],
+            )?
+        } else {
+            // A new branch, or one whose remote tip we haven't fetched: every
+            // commit the remote doesn't have yet.
+            let not_on_remote = format!("--remotes={}", remote);
+            git_paths(
+                work_tree,
+                &[
+                    "log",
+                    "--format=",
+                    "--name-only",
+                    "-z",
+                    "--diff-filter=ACMR",
+                    local_sha,
+                    "--not",
+                    &not_on_remote,
+                ],
+            )?
+        };
+
+        for file in changed {
+            if !files.contains(&file) {
+                files.push(file);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+fn is_null(sha: &str) -> bool {
+    sha.chars().all(|c| c == '0')
+}
+
+fn object_exists(work_tree: &Path, sha: &str) -> bool {
+    std::process::Command::new("git")
+        .current_dir(work_tree)
+        .args(["cat-file", "-e", &format!("{}^{{commit}}", sha)])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Runs a git command printing NUL-separated paths and collects them.
+fn git_paths(work_tree: &Path, args: &[&str]) -> Result<Vec<String>> {
+    let output = std::process::Command::new("git")
+        .current_dir(work_tree)
+        .args(args)
+        .output()?;
+
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .map(|path| path.trim_start_matches('\n'))
+        .filter(|path| !path.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_null() {
+        assert!(is_null("0000000000000000000000000000000000000000"));
+        assert!(!is_null("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"));
+    }
+}
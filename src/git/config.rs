@@ -0,0 +1,416 @@
+use crate::error::HookmanError;
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Maximum nesting of `include.path` directives, matching Git's own limit.
+const MAX_INCLUDE_DEPTH: usize = 10;
+
+/// A minimal reader for Git's configuration files.
+///
+/// Entries are kept in the order Git would see them (system, global, then
+/// local), so the last value for a key is the effective one.
+#[derive(Debug, Default)]
+pub struct GitConfig {
+    entries: Vec<(String, String)>,
+}
+
+impl GitConfig {
+    /// Loads the system, global and repository configuration files for a
+    /// repository, honoring `GIT_CONFIG_NOSYSTEM`, `GIT_CONFIG_SYSTEM` and
+    /// `GIT_CONFIG_GLOBAL` like Git does.
+    pub fn load(git_dir: &Path, common_dir: &Path) -> Result<Self> {
+        let mut config = GitConfig::default();
+
+        if !env_flag("GIT_CONFIG_NOSYSTEM") {
+            let system = std::env::var_os("GIT_CONFIG_SYSTEM")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from("/etc/gitconfig"));
+            config.read_file(&system)?;
+        }
+
+        for global in global_config_files() {
+            config.read_file(&global)?;
+        }
+
+        config.read_file(&common_dir.join("config"))?;
+
+        if config.get_bool("extensions.worktreeConfig") == Some(true) {
+            config.read_file(&git_dir.join("config.worktree"))?;
+        }
+
+        Ok(config)
+    }
+
+    /// Returns the effective value for a `section.key` or
+    /// `section.subsection.key` name.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        let name = normalize_key(name);
+        self.entries
+            .iter()
+            .rev()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        self.get(name).and_then(parse_bool)
+    }
+
+    /// Reads a config file if it exists; missing files are not an error.
+    pub fn read_file(&mut self, path: &Path) -> Result<()> {
+        self.read_file_at_depth(path, 0)
+    }
+
+    fn read_file_at_depth(&mut self, path: &Path, depth: usize) -> Result<()> {
+        if !path.is_file() {
+            return Ok(());
+        }
+
+        let contents = fs::read_to_string(path)
+            .map_err(|e| HookmanError::ConfigReadError(format!("{}: {}", path.display(), e)))?;
+        self.parse(&contents, path, depth)
+    }
+
+    fn parse(&mut self, contents: &str, path: &Path, depth: usize) -> Result<()> {
+        let mut section = String::new();
+        let mut lines = contents.lines();
+
+        while let Some(line) = lines.next() {
+            let mut line = line.trim_start().to_string();
+
+            // A trailing backslash continues the value on the next line.
+            while line.ends_with('\\') && !line.ends_with("\\\\") {
+                line.pop();
+                match lines.next() {
+                    Some(next) => line.push_str(next),
+                    None => break,
+                }
+            }
+
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if line.starts_with('[') {
+                section = parse_section(&line).ok_or_else(|| {
+                    HookmanError::ConfigReadError(format!(
+                        "{}: invalid section header '{}'",
+                        path.display(),
+                        line.trim()
+                    ))
+                })?;
+                continue;
+            }
+
+            if section.is_empty() {
+                continue;
+            }
+
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), parse_value(value)),
+                None => (strip_comment(&line).trim(), "true".to_string()),
+            };
+
+            let name = format!("{}.{}", section, key.to_ascii_lowercase());
+
+            if name == "include.path" {
+                if depth >= MAX_INCLUDE_DEPTH {
+                    return Err(HookmanError::ConfigReadError(format!(
+                        "{}: exceeded maximum include depth",
+                        path.display()
+                    ))
+                    .into());
+                }
+                let base = path.parent().unwrap_or(Path::new(""));
+                let include = expand_path(&value, base);
+                self.read_file_at_depth(&include, depth + 1)?;
+            }
+
+            self.entries.push((name, value));
+        }
+
+        Ok(())
+    }
+}
+
+/// Resolves a path-valued config entry: `~/` expands to the home directory
+/// and relative paths are taken relative to `base`.
+pub fn expand_path(value: &str, base: &Path) -> PathBuf {
+    if let Some(rest) = value.strip_prefix("~/") {
+        if let Some(home) = home_dir() {
+            return home.join(rest);
+        }
+    }
+    base.join(value)
+}
+
+fn global_config_files() -> Vec<PathBuf> {
+    if let Some(global) = std::env::var_os("GIT_CONFIG_GLOBAL") {
+        return vec![PathBuf::from(global)];
+    }
+
+    let mut files = Vec::new();
+    let xdg = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".config")));
+    if let Some(xdg) = xdg {
+        files.push(xdg.join("git/config"));
+    }
+    if let Some(home) = home_dir() {
+        files.push(home.join(".gitconfig"));
+    }
+    files
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+}
+
+fn env_flag(name: &str) -> bool {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| parse_bool(&value))
+        .unwrap_or(false)
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" | "" => Some(false),
+        _ => None,
+    }
+}
+
+/// Lowercases the section and key of a config name, preserving the case of
+/// any subsection in between.
+fn normalize_key(name: &str) -> String {
+    match (name.find('.'), name.rfind('.')) {
+        (Some(first), Some(last)) if first != last => format!(
+            "{}{}{}",
+            name[..first].to_ascii_lowercase(),
+            &name[first..last],
+            name[last..].to_ascii_lowercase()
+        ),
+        _ => name.to_ascii_lowercase(),
+    }
+}
+
+/// Parses `[section]`, `[section "subsection"]` and the legacy
+/// `[section.subsection]` header forms.
+fn parse_section(line: &str) -> Option<String> {
+    let end = line.find(']')?;
+    let header = line[1..end].trim();
+
+    if let Some((name, rest)) = header.split_once(char::is_whitespace) {
+        let subsection = rest.trim().strip_prefix('"')?.strip_suffix('"')?;
+        let subsection = subsection.replace("\\\"", "\"").replace("\\\\", "\\");
+        return Some(format!("{}.{}", name.to_ascii_lowercase(), subsection));
+    }
+
+    if header.is_empty() {
+        return None;
+    }
+
+    Some(normalize_key(header))
+}
+
+/// Parses a value, handling double quotes, escape sequences and trailing
+/// comments.
+fn parse_value(raw: &str) -> String {
+    let mut value = String::new();
+    let mut in_quotes = false;
+    let mut pending_space = String::new();
+    let mut chars = raw.trim().chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                value.push_str(&pending_space);
+                pending_space.clear();
+                in_quotes = !in_quotes;
+            }
+            '\\' => {
+                value.push_str(&pending_space);
+                pending_space.clear();
+                match chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('b') => {
+                        value.pop();
+                    }
+                    Some(other) => value.push(other),
+                    None => {}
+                }
+            }
+            '#' | ';' if !in_quotes => break,
+            c if c.is_whitespace() && !in_quotes => pending_space.push(c),
+            c => {
+                value.push_str(&pending_space);
+                pending_space.clear();
+                value.push(c);
+            }
+        }
+    }
+
+    value
+}
+
+fn strip_comment(line: &str) -> &str {
+    match line.find(['#', ';']) {
+        Some(index) => &line[..index],
+        None => line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn parse(contents: &str) -> GitConfig {
+        let mut config = GitConfig::default();
+        config.parse(contents, Path::new("/tmp/config"), 0).unwrap();
+        config
+    }
+
+    #[test]
+    fn test_parse_simple_values() {
+        let config = parse(
+            "[core]\n\
+             \trepositoryformatversion = 0\n\
+             \tbare = false\n\
+             \thooksPath = .githooks\n",
+        );
+
+        assert_eq!(config.get("core.hooksPath"), Some(".githooks"));
+        assert_eq!(config.get("core.hookspath"), Some(".githooks"));
+        assert_eq!(config.get_bool("core.bare"), Some(false));
+        assert_eq!(config.get("core.missing"), None);
+    }
+
+    #[test]
+    fn test_last_value_wins() {
+        let config = parse("[core]\nhooksPath = first\n[CORE]\nHOOKSPATH = second\n");
+
+        assert_eq!(config.get("core.hooksPath"), Some("second"));
+    }
+
+    #[test]
+    fn test_parse_subsections() {
+        let config = parse(
+            "[remote \"Origin\"]\n\
+             url = git@example.com:repo.git\n\
+             [branch.main]\n\
+             remote = origin\n",
+        );
+
+        assert_eq!(
+            config.get("remote.Origin.url"),
+            Some("git@example.com:repo.git")
+        );
+        assert_eq!(config.get("remote.origin.url"), None);
+        assert_eq!(config.get("branch.main.remote"), Some("origin"));
+    }
+
+    #[test]
+    fn test_parse_quotes_and_comments() {
+        let config = parse(
+            "# comment\n\
+             ; another comment\n\
+             [core]\n\
+             hooksPath = \"path with spaces\" # trailing comment\n\
+             editor = vim ; comment\n\
+             pager = \"less # not a comment\"\n\
+             escaped = \"a\\\"b\\\\c\"\n\
+             filemode\n",
+        );
+
+        assert_eq!(config.get("core.hooksPath"), Some("path with spaces"));
+        assert_eq!(config.get("core.editor"), Some("vim"));
+        assert_eq!(config.get("core.pager"), Some("less # not a comment"));
+        assert_eq!(config.get("core.escaped"), Some("a\"b\\c"));
+        assert_eq!(config.get_bool("core.filemode"), Some(true));
+    }
+
+    #[test]
+    fn test_parse_line_continuation() {
+        let config = parse("[alias]\nlg = log \\\n--oneline\n");
+
+        assert_eq!(config.get("alias.lg"), Some("log --oneline"));
+    }
+
+    #[test]
+    fn test_include_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let included = temp_dir.path().join("included.conf");
+        fs::write(&included, "[core]\nhooksPath = /from/include\n").unwrap();
+        let main = temp_dir.path().join("config");
+        fs::write(
+            &main,
+            "[core]\nhooksPath = /before\n[include]\npath = included.conf\n",
+        )
+        .unwrap();
+
+        let mut config = GitConfig::default();
+        config.read_file(&main).unwrap();
+
+        assert_eq!(config.get("core.hooksPath"), Some("/from/include"));
+    }
+
+    #[test]
+    fn test_include_is_overridden_by_later_values() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("included.conf"),
+            "[core]\nhooksPath = /from/include\n",
+        )
+        .unwrap();
+        let main = temp_dir.path().join("config");
+        fs::write(
+            &main,
+            "[include]\npath = included.conf\n[core]\nhooksPath = /after\n",
+        )
+        .unwrap();
+
+        let mut config = GitConfig::default();
+        config.read_file(&main).unwrap();
+
+        assert_eq!(config.get("core.hooksPath"), Some("/after"));
+    }
+
+    #[test]
+    fn test_include_cycle_is_an_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let main = temp_dir.path().join("config");
+        fs::write(&main, "[include]\npath = config\n").unwrap();
+
+        let mut config = GitConfig::default();
+
+        assert!(config.read_file(&main).is_err());
+    }
+
+    #[test]
+    fn test_missing_file_is_ignored() {
+        let mut config = GitConfig::default();
+
+        config.read_file(Path::new("/nonexistent/config")).unwrap();
+
+        assert_eq!(config.get("core.hooksPath"), None);
+    }
+
+    #[test]
+    fn test_expand_path() {
+        assert_eq!(
+            expand_path("hooks", Path::new("/repo")),
+            PathBuf::from("/repo/hooks")
+        );
+        assert_eq!(
+            expand_path("/abs/hooks", Path::new("/repo")),
+            PathBuf::from("/abs/hooks")
+        );
+    }
+}
@@ -0,0 +1,7 @@
+pub mod changes;
+mod config;
+pub mod refs;
+mod repository;
+pub mod stash;
+
+pub use repository::Repository;
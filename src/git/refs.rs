@@ -0,0 +1,49 @@
+use anyhow::{bail, Result};
+use std::path::Path;
+
+/// The checked-out branch, or `None` when HEAD is detached.
+pub fn current_branch(work_tree: &Path) -> Result<Option<String>> {
+    let output = std::process::Command::new("git")
+        .current_dir(work_tree)
+        .args(["symbolic-ref", "--short", "-q", "HEAD"])
+        .output()?;
+
+    match output.status.code() {
+        Some(0) => Ok(Some(
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        )),
+        // Exit code 1 with -q means HEAD is not a symbolic ref.
+        Some(1) => Ok(None),
+        _ => bail!(
+            "git symbolic-ref failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+    }
+}
+
+/// The branches a pre-push hook is pushing to. `refs` is the hook's stdin:
+/// one `<local ref> <local sha> <remote ref> <remote sha>` line per ref.
+pub fn pushed_branches(refs: &str) -> Vec<String> {
+    refs.lines()
+        .filter_map(|line| line.split_whitespace().nth(2))
+        .filter_map(|remote_ref| remote_ref.strip_prefix("refs/heads/"))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pushed_branches() {
+        let refs = "refs/heads/topic 1111 refs/heads/main 2222\n\
+                    refs/tags/v1 3333 refs/tags/v1 0000\n\
+                    (delete) 0000 refs/heads/release/1.0 4444\n";
+
+        assert_eq!(
+            pushed_branches(refs),
+            vec!["main".to_string(), "release/1.0".to_string()]
+        );
+    }
+}
@@ -0,0 +1,403 @@
+use crate::error::HookmanError;
+use crate::git::config::{expand_path, GitConfig};
+use crate::storage::HOOKMAN_DIR;
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Git environment variables that override repository discovery.
+#[derive(Debug, Clone, Default)]
+pub struct GitEnv {
+    pub git_dir: Option<PathBuf>,
+    pub common_dir: Option<PathBuf>,
+    pub work_tree: Option<PathBuf>,
+}
+
+impl GitEnv {
+    pub fn from_env() -> Self {
+        let var = |name: &str| {
+            std::env::var_os(name)
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from)
+        };
+
+        GitEnv {
+            git_dir: var("GIT_DIR"),
+            common_dir: var("GIT_COMMON_DIR"),
+            work_tree: var("GIT_WORK_TREE"),
+        }
+    }
+}
+
+/// A Git repository located by walking up from a starting directory.
+///
+/// `git_dir` is the per-worktree directory (`.git` itself, or the directory a
+/// `.git` file points to), while `common_dir` holds state shared by all
+/// worktrees, including the hooks directory.
+#[derive(Debug, Clone)]
+pub struct Repository {
+    work_tree: PathBuf,
+    git_dir: PathBuf,
+    common_dir: PathBuf,
+}
+
+impl Repository {
+    /// Discovers the repository containing `start`, or the current directory
+    /// when no path is given. Relative paths are resolved against the current
+    /// directory.
+    pub fn discover(start: Option<&Path>) -> Result<Self> {
+        let current_dir = std::env::current_dir()?;
+        let start = match start {
+            Some(path) => current_dir.join(path),
+            None => current_dir,
+        };
+
+        if !start.is_dir() {
+            return Err(HookmanError::InvalidRepoPath(start.display().to_string()).into());
+        }
+
+        let start = start.canonicalize()?;
+        Self::discover_with_env(&start, &GitEnv::from_env())
+    }
+
+    /// Discovers the repository from `start`, applying `GIT_DIR`-style
+    /// overrides the same way Git does: relative override paths are resolved
+    /// against `start`, and an explicit git dir without a work tree uses
+    /// `start` as the work tree.
+    pub fn discover_with_env(start: &Path, env: &GitEnv) -> Result<Self> {
+        let (work_tree, git_dir) = match &env.git_dir {
+            Some(git_dir) => {
+                let git_dir = canonicalize_git_dir(&start.join(git_dir))?;
+                let work_tree = match &env.work_tree {
+                    Some(work_tree) => start.join(work_tree),
+                    None => start.to_path_buf(),
+                };
+                (work_tree, git_dir)
+            }
+            None => Self::find_git_dir(start)?,
+        };
+
+        let common_dir = match &env.common_dir {
+            Some(common_dir) => canonicalize_git_dir(&start.join(common_dir))?,
+            None => read_common_dir(&git_dir)?,
+        };
+
+        Ok(Repository {
+            work_tree,
+            git_dir,
+            common_dir,
+        })
+    }
+
+    fn find_git_dir(start: &Path) -> Result<(PathBuf, PathBuf)> {
+        for dir in start.ancestors() {
+            let dot_git = dir.join(".git");
+            if dot_git.is_dir() {
+                return Ok((dir.to_path_buf(), dot_git));
+            }
+            if dot_git.is_file() {
+                let git_dir = read_gitdir_file(&dot_git)?;
+                return Ok((dir.to_path_buf(), git_dir));
+            }
+        }
+
+        Err(HookmanError::NotInGitRepo.into())
+    }
+
+    pub fn work_tree(&self) -> &Path {
+        &self.work_tree
+    }
+
+    #[allow(dead_code)]
+    pub fn git_dir(&self) -> &Path {
+        &self.git_dir
+    }
+
+    #[allow(dead_code)]
+    pub fn common_dir(&self) -> &Path {
+        &self.common_dir
+    }
+
+    pub fn hookman_dir(&self) -> PathBuf {
+        self.work_tree.join(HOOKMAN_DIR)
+    }
+
+    /// Hookman's private state inside the Git directory (backups and the
+    /// like). Shared by all worktrees, like the hooks themselves.
+    pub fn state_dir(&self) -> PathBuf {
+        self.common_dir.join("hookman")
+    }
+
+    pub fn backups_dir(&self) -> PathBuf {
+        self.state_dir().join("backups")
+    }
+
+    /// Where pre-existing hooks are preserved for hooks in chain mode.
+    pub fn chained_hooks_dir(&self) -> PathBuf {
+        self.state_dir().join("chained")
+    }
+
+    /// Where unstaged changes are set aside while pre-commit commands run.
+    /// Per worktree, since each has its own index and files.
+    pub fn stash_dir(&self) -> PathBuf {
+        self.git_dir.join("hookman").join("stash")
+    }
+
+    pub fn config(&self) -> Result<GitConfig> {
+        GitConfig::load(&self.git_dir, &self.common_dir)
+    }
+
+    /// The hooks directory Git uses when `core.hooksPath` is not set. Hooks
+    /// are shared by every worktree, so it lives in the common dir.
+    pub fn default_hooks_dir(&self) -> PathBuf {
+        self.common_dir.join("hooks")
+    }
+
+    /// The directory named by `core.hooksPath`, if set. Relative paths are
+    /// resolved against the work tree, where Git runs hooks from.
+    pub fn configured_hooks_path(&self) -> Result<Option<PathBuf>> {
+        Ok(self.hooks_path_in(&self.config()?))
+    }
+
+    fn hooks_path_in(&self, config: &GitConfig) -> Option<PathBuf> {
+        config
+            .get("core.hooksPath")
+            .filter(|path| !path.is_empty())
+            .map(|path| expand_path(path, &self.work_tree))
+    }
+
+    /// The directory Git actually runs hooks from.
+    pub fn git_hooks_dir(&self) -> Result<PathBuf> {
+        Ok(self
+            .configured_hooks_path()?
+            .unwrap_or_else(|| self.default_hooks_dir()))
+    }
+}
+
+/// Follows a `.git` file of the form `gitdir: <path>`, as written for linked
+/// worktrees and submodules. Relative paths are relative to the file itself.
+fn read_gitdir_file(path: &Path) -> Result<PathBuf> {
+    let contents = fs::read_to_string(path)?;
+    let target = contents
+        .lines()
+        .find_map(|line| line.strip_prefix("gitdir:"))
+        .map(str::trim)
+        .filter(|target| !target.is_empty())
+        .ok_or_else(|| {
+            HookmanError::InvalidGitDir(format!("{} has no gitdir entry", path.display()))
+        })?;
+
+    let base = path.parent().unwrap_or(Path::new(""));
+    canonicalize_git_dir(&base.join(target))
+}
+
+/// Resolves the common dir of a linked worktree from its `commondir` file,
+/// falling back to the git dir itself for regular repositories.
+fn read_common_dir(git_dir: &Path) -> Result<PathBuf> {
+    let commondir_file = git_dir.join("commondir");
+    if !commondir_file.is_file() {
+        return Ok(git_dir.to_path_buf());
+    }
+
+    let contents = fs::read_to_string(&commondir_file)?;
+    let target = contents.trim();
+    if target.is_empty() {
+        return Ok(git_dir.to_path_buf());
+    }
+
+    canonicalize_git_dir(&git_dir.join(target))
+}
+
+fn canonicalize_git_dir(path: &Path) -> Result<PathBuf> {
+    path.canonicalize()
+        .ok()
+        .filter(|path| path.is_dir())
+        .ok_or_else(|| {
+            HookmanError::InvalidGitDir(format!("{} is not a directory", path.display())).into()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup_repo() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join(".git/hooks")).unwrap();
+        temp_dir
+    }
+
+    fn discover_from(start: &Path) -> Result<Repository> {
+        Repository::discover_with_env(start, &GitEnv::default())
+    }
+
+    #[test]
+    fn test_discover_from_root() {
+        let temp_dir = setup_repo();
+        let root = temp_dir.path().canonicalize().unwrap();
+
+        let repo = discover_from(&root).unwrap();
+
+        assert_eq!(repo.work_tree(), root);
+        assert_eq!(repo.git_dir(), root.join(".git"));
+        assert_eq!(repo.common_dir(), root.join(".git"));
+        assert_eq!(repo.hookman_dir(), root.join(".hookman"));
+        assert_eq!(repo.default_hooks_dir(), root.join(".git/hooks"));
+    }
+
+    #[test]
+    fn test_discover_from_nested_directory() {
+        let temp_dir = setup_repo();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let nested = root.join("src/deeply/nested");
+        fs::create_dir_all(&nested).unwrap();
+
+        let repo = discover_from(&nested).unwrap();
+
+        assert_eq!(repo.work_tree(), root);
+    }
+
+    #[test]
+    fn test_discover_outside_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+
+        let err = discover_from(&root).unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<HookmanError>(),
+            Some(HookmanError::NotInGitRepo)
+        ));
+    }
+
+    #[test]
+    fn test_discover_explicit_path() {
+        let temp_dir = setup_repo();
+        let root = temp_dir.path().canonicalize().unwrap();
+
+        let repo = Repository::discover(Some(&root)).unwrap();
+
+        assert_eq!(repo.work_tree(), root);
+    }
+
+    #[test]
+    fn test_discover_missing_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing = temp_dir.path().join("missing");
+
+        let err = Repository::discover(Some(&missing)).unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<HookmanError>(),
+            Some(HookmanError::InvalidRepoPath(_))
+        ));
+    }
+
+    #[test]
+    fn test_discover_linked_worktree() {
+        let temp_dir = setup_repo();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let worktree_git_dir = root.join(".git/worktrees/feature");
+        fs::create_dir_all(&worktree_git_dir).unwrap();
+        fs::write(worktree_git_dir.join("commondir"), "../..\n").unwrap();
+        let worktree = root.join("feature");
+        fs::create_dir_all(&worktree).unwrap();
+        fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", worktree_git_dir.display()),
+        )
+        .unwrap();
+
+        let repo = discover_from(&worktree).unwrap();
+
+        assert_eq!(repo.work_tree(), worktree);
+        assert_eq!(repo.git_dir(), worktree_git_dir);
+        assert_eq!(repo.common_dir(), root.join(".git"));
+        assert_eq!(repo.default_hooks_dir(), root.join(".git/hooks"));
+        assert_eq!(repo.hookman_dir(), worktree.join(".hookman"));
+    }
+
+    #[test]
+    fn test_discover_relative_gitdir_file() {
+        let temp_dir = setup_repo();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let module_git_dir = root.join(".git/modules/sub");
+        fs::create_dir_all(&module_git_dir).unwrap();
+        let submodule = root.join("sub");
+        fs::create_dir_all(&submodule).unwrap();
+        fs::write(submodule.join(".git"), "gitdir: ../.git/modules/sub\n").unwrap();
+
+        let repo = discover_from(&submodule).unwrap();
+
+        assert_eq!(repo.work_tree(), submodule);
+        assert_eq!(repo.git_dir(), module_git_dir);
+        assert_eq!(repo.default_hooks_dir(), module_git_dir.join("hooks"));
+    }
+
+    #[test]
+    fn test_discover_invalid_gitdir_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        fs::write(root.join(".git"), "gitdir: missing\n").unwrap();
+
+        let err = discover_from(&root).unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<HookmanError>(),
+            Some(HookmanError::InvalidGitDir(_))
+        ));
+    }
+
+    #[test]
+    fn test_discover_with_env_overrides() {
+        let temp_dir = setup_repo();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let shared = root.join("shared");
+        fs::create_dir_all(&shared).unwrap();
+        let env = GitEnv {
+            git_dir: Some(PathBuf::from(".git")),
+            common_dir: Some(PathBuf::from("shared")),
+            work_tree: None,
+        };
+
+        let repo = Repository::discover_with_env(&root, &env).unwrap();
+
+        assert_eq!(repo.work_tree(), root);
+        assert_eq!(repo.git_dir(), root.join(".git"));
+        assert_eq!(repo.default_hooks_dir(), shared.join("hooks"));
+    }
+
+    #[test]
+    fn test_hooks_dir_defaults_to_common_dir() {
+        let temp_dir = setup_repo();
+        let root = temp_dir.path().canonicalize().unwrap();
+        fs::write(root.join(".git/config"), "[core]\n\tbare = false\n").unwrap();
+        // Only the repository's own config, so a core.hooksPath in the
+        // developer's global config can't leak in.
+        let mut config = GitConfig::default();
+        config.read_file(&root.join(".git/config")).unwrap();
+
+        let repo = discover_from(&root).unwrap();
+
+        assert_eq!(repo.hooks_path_in(&config), None);
+    }
+
+    #[test]
+    fn test_hooks_dir_from_local_config() {
+        let temp_dir = setup_repo();
+        let root = temp_dir.path().canonicalize().unwrap();
+        fs::write(
+            root.join(".git/config"),
+            "[core]\n\thooksPath = .githooks\n",
+        )
+        .unwrap();
+
+        let repo = discover_from(&root).unwrap();
+
+        assert_eq!(
+            repo.configured_hooks_path().unwrap(),
+            Some(root.join(".githooks"))
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use crate::storage::HOOKMAN_DIR;
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const PATCH_FILE: &str = "unstaged.patch";
+const UNTRACKED_DIR: &str = "untracked";
+
+/// Unstaged modifications and untracked files set aside while hook commands
+/// run, so they see exactly what is about to be committed.
+///
+/// Modifications are saved as a binary patch and the work tree is reset to
+/// the index; untracked files are moved into the stash directory. Dropping a
+/// stash that hasn't been restored restores it.
+#[derive(Debug)]
+pub struct Stash {
+    work_tree: PathBuf,
+    dir: PathBuf,
+    /// Paths with unstaged modifications, when there are any.
+    modified: Vec<String>,
+    untracked: Vec<String>,
+    restored: bool,
+}
+
+impl Stash {
+    /// Saves unstaged changes and untracked files into `dir`, leaving the work
+    /// tree matching the index. Returns `None` if there was nothing to save.
+    pub fn save(work_tree: &Path, dir: &Path) -> Result<Option<Stash>> {
+        let exclude_config = format!(":(exclude){}", HOOKMAN_DIR);
+        let untracked = paths(&git(
+            work_tree,
+            &[
+                "ls-files",
+                "--others",
+                "--exclude-standard",
+                "-z",
+                "--",
+                ".",
+                &exclude_config,
+            ],
+        )?);
+        let modified = paths(&git(work_tree, &diff_args(&["--name-only", "-z"]))?);
+
+        if modified.is_empty() && untracked.is_empty() {
+            return Ok(None);
+        }
+
+        if dir.exists() {
+            bail!(
+                "Found changes stashed by an earlier hook run in {}; restore them with 'git apply' or remove the directory",
+                dir.display()
+            );
+        }
+        fs::create_dir_all(dir)?;
+
+        let mut stash = Stash {
+            work_tree: work_tree.to_path_buf(),
+            dir: dir.to_path_buf(),
+            modified: Vec::new(),
+            untracked: Vec::new(),
+            restored: false,
+        };
+
+        if !modified.is_empty() {
+            let patch = git(work_tree, &diff_args(&["--binary"]))?;
+            fs::write(stash.patch_path(), &patch.stdout)?;
+            stash.modified = modified;
+            git(
+                work_tree,
+                &["-c", "submodule.recurse=0", "checkout", "--", "."],
+            )?;
+        }
+
+        for path in untracked {
+            let stashed = stash.dir.join(UNTRACKED_DIR).join(&path);
+            if let Some(parent) = stashed.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(work_tree.join(&path), &stashed)
+                .with_context(|| format!("Failed to stash untracked file {}", path))?;
+            stash.untracked.push(path);
+        }
+
+        Ok(Some(stash))
+    }
+
+    fn patch_path(&self) -> PathBuf {
+        self.dir.join(PATCH_FILE)
+    }
+
+    /// Puts the stashed changes back. If a command changed a file that had
+    /// unstaged changes (or created a file that was untracked), the stashed
+    /// version wins and the command's change is discarded; those paths are
+    /// returned.
+    ///
+    /// Whatever can't be put back is moved out of the way, so it doesn't
+    /// block the next run, and the error says where it is.
+    pub fn restore(&mut self) -> Result<Vec<String>> {
+        if self.restored {
+            return Ok(Vec::new());
+        }
+
+        let mut conflicts = Vec::new();
+        let mut patch_failed = false;
+
+        if !self.modified.is_empty() && !self.apply_patch()? {
+            let changed = paths(&git(&self.work_tree, &diff_args(&["--name-only", "-z"]))?);
+            conflicts.extend(
+                changed
+                    .into_iter()
+                    .filter(|path| self.modified.contains(path)),
+            );
+
+            let mut checkout = vec!["-c", "submodule.recurse=0", "checkout", "--"];
+            if conflicts.is_empty() {
+                checkout.push(".");
+            } else {
+                checkout.extend(conflicts.iter().map(String::as_str));
+            }
+            git(&self.work_tree, &checkout)?;
+
+            patch_failed = !self.apply_patch()?;
+        }
+        // Never apply the patch twice, even if what follows fails.
+        self.modified.clear();
+
+        let mut stranded = Vec::new();
+        for path in std::mem::take(&mut self.untracked) {
+            match self.restore_untracked(&path) {
+                Ok(true) => conflicts.push(path),
+                Ok(false) => {}
+                Err(_) => stranded.push(path),
+            }
+        }
+        self.restored = true;
+
+        if !patch_failed && stranded.is_empty() {
+            fs::remove_dir_all(&self.dir)?;
+            return Ok(conflicts);
+        }
+
+        let kept = self.keep()?;
+        let mut saved = Vec::new();
+        if patch_failed {
+            saved.push(format!(
+                "unstaged changes are saved in {}",
+                kept.join(PATCH_FILE).display()
+            ));
+        }
+        if !stranded.is_empty() {
+            saved.push(format!(
+                "untracked files {} are saved in {}",
+                stranded.join(", "),
+                kept.join(UNTRACKED_DIR).display()
+            ));
+        }
+        bail!("Failed to restore stashed changes; {}", saved.join(", "));
+    }
+
+    /// Moves an untracked file back, replacing any file a command created in
+    /// its place. Returns whether one was replaced.
+    fn restore_untracked(&self, path: &str) -> Result<bool> {
+        let target = self.work_tree.join(path);
+        let replaced = target.symlink_metadata().is_ok();
+        if replaced {
+            fs::remove_file(&target)?;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(self.dir.join(UNTRACKED_DIR).join(path), &target)?;
+        Ok(replaced)
+    }
+
+    /// Renames the stash directory to a timestamped one next to it, for the
+    /// user to recover from, and returns the new path.
+    fn keep(&self) -> Result<PathBuf> {
+        let name = self.dir.file_name().unwrap_or_default().to_string_lossy();
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let mut kept = self.dir.with_file_name(format!("{}-{}", name, secs));
+        let mut suffix = 1;
+        while kept.exists() {
+            kept = self
+                .dir
+                .with_file_name(format!("{}-{}-{}", name, secs, suffix));
+            suffix += 1;
+        }
+        fs::rename(&self.dir, &kept)?;
+        Ok(kept)
+    }
+
+    fn apply_patch(&self) -> Result<bool> {
+        let status = std::process::Command::new("git")
+            .current_dir(&self.work_tree)
+            .args(["apply", "--whitespace=nowarn"])
+            .arg(self.patch_path())
+            .stderr(std::process::Stdio::null())
+            .status()?;
+        Ok(status.success())
+    }
+}
+
+impl Drop for Stash {
+    fn drop(&mut self) {
+        if let Err(e) = self.restore() {
+            eprintln!("hookman: {}", e);
+        }
+    }
+}
+
+/// `git diff` of the work tree against the index, with output independent of
+/// user configuration.
+fn diff_args<'a>(extra: &[&'a str]) -> Vec<&'a str> {
+    let mut args = vec![
+        "diff",
+        "--no-color",
+        "--no-ext-diff",
+        "--no-renames",
+        "--ignore-submodules",
+    ];
+    args.extend_from_slice(extra);
+    args
+}
+
+fn git(work_tree: &Path, args: &[&str]) -> Result<Output> {
+    let output = std::process::Command::new("git")
+        .current_dir(work_tree)
+        .args(args)
+        .output()?;
+
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.iter()
+                .find(|arg| !arg.starts_with('-') && !arg.contains('='))
+                .unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(output)
+}
+
+fn paths(output: &Output) -> Vec<String> {
+    String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(str::to_string)
+        .collect()
+}
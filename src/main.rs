@@ -2,30 +2,76 @@ mod cli;
 mod commands;
 mod error;
 mod generator;
+mod git;
 mod models;
+mod runner;
 mod storage;
 
 use anyhow::Result;
 use clap::Parser;
-use cli::{Cli, Commands};
+use cli::{BackupsCommand, Cli, Commands};
+use git::Repository;
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    let repo = Repository::discover(cli.repo.as_deref())?;
 
     match cli.command {
-        Commands::Init => commands::init::execute(),
+        Commands::Init => commands::init::execute(&repo),
         Commands::Add {
             hook_type,
             command,
             id,
             description,
-        } => commands::add::execute(hook_type, command, id, description),
+            position,
+        } => commands::add::execute(&repo, hook_type, command, id, description, position),
         Commands::Remove {
             hook_type,
             command_id,
-        } => commands::remove::execute(hook_type, command_id),
-        Commands::List { hook_type } => commands::list::execute(hook_type),
-        Commands::Apply { dry_run } => commands::apply::execute(dry_run),
-        Commands::Status => commands::status::execute(),
+        } => commands::remove::execute(&repo, hook_type, command_id),
+        Commands::Edit {
+            hook_type,
+            command_id,
+            changes,
+        } => commands::edit::execute(&repo, hook_type, command_id, *changes),
+        Commands::Move {
+            hook_type,
+            command_id,
+            placement,
+        } => commands::reorder::execute(&repo, hook_type, command_id, placement),
+        Commands::Disable {
+            hook_type,
+            command_id,
+        } => commands::toggle::disable(&repo, hook_type, command_id),
+        Commands::Enable {
+            hook_type,
+            command_id,
+        } => commands::toggle::enable(&repo, hook_type, command_id),
+        Commands::Mv {
+            from_hook,
+            command_id,
+            to_hook,
+            new_id,
+        } => commands::transfer::mv(&repo, from_hook, command_id, to_hook, new_id),
+        Commands::Cp {
+            from_hook,
+            command_id,
+            to_hook,
+            new_id,
+        } => commands::transfer::cp(&repo, from_hook, command_id, to_hook, new_id),
+        Commands::List { hook_type } => commands::list::execute(&repo, hook_type),
+        Commands::Apply { dry_run } => commands::apply::execute(&repo, dry_run),
+        Commands::Uninstall {
+            hook_types,
+            dry_run,
+        } => commands::uninstall::execute(&repo, hook_types, dry_run),
+        Commands::Backups { command } => match command {
+            BackupsCommand::List { hook_type } => commands::backups::list(&repo, hook_type),
+            BackupsCommand::Restore { hook_type, id } => {
+                commands::backups::restore(&repo, hook_type, id)
+            }
+        },
+        Commands::Status { check } => commands::status::execute(&repo, check),
+        Commands::Run { hook_type, args } => commands::run::execute(&repo, hook_type, args),
     }
 }
@@ -1,19 +1,160 @@
+use crate::error::HookmanError;
+use crate::models::{FileFilter, Timeout, When};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Component, PathBuf};
+
+/// ID under which a chained pre-existing hook is reported and skipped, so no
+/// command may use it.
+pub const CHAINED_ID: &str = "chained";
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Command {
     pub id: String,
+    /// Disabled commands stay in the configuration but don't run.
+    #[serde(
+        default = "default_enabled",
+        skip_serializing_if = "super::hook::is_true"
+    )]
+    pub enabled: bool,
+    /// A one-line shell command. Empty when the command uses `script`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub command: String,
+    /// A multi-line script, as an alternative to `command`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
     pub description: Option<String>,
+    /// Kill the command if it runs longer than this. Falls back to the
+    /// hook's `timeout` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<Timeout>,
+    /// Report a failure of this command without failing the hook.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_failure: bool,
+    /// IDs of commands in the same hook that must pass before this one runs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// Only run when a changed file matches one of these globs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+    /// Ignore changed files matching these globs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// The command rewrites files (a formatter, say). In pre-commit, staged
+    /// files it modifies are re-staged or fail the hook, per the hook's
+    /// `fix_mode`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fixer: bool,
+    /// Conditions under which the command runs; it is skipped otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<When>,
+    /// Environment variables set for the command, on top of the hook's `env`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Directory to run the command in, relative to the repository root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<PathBuf>,
+    /// Interpreter for the command, such as `bash`, `python3` or a path.
+    /// Defaults to the hook's `shell`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+}
+
+fn default_enabled() -> bool {
+    true
 }
 
 impl Command {
     pub fn new(id: String, command: String, description: Option<String>) -> Self {
         Command {
             id,
+            enabled: true,
             command,
+            script: None,
             description,
+            timeout: None,
+            allow_failure: false,
+            depends_on: Vec::new(),
+            files: Vec::new(),
+            exclude: Vec::new(),
+            fixer: false,
+            when: None,
+            env: BTreeMap::new(),
+            cwd: None,
+            shell: None,
+        }
+    }
+
+    /// Checks that `id` is usable as a command ID: non-empty, made of ASCII
+    /// letters, digits, `-`, `_` and `.`, and not starting with `-`, so it
+    /// can be typed on a command line and listed in `HOOKMAN_SKIP`.
+    pub fn validate_id(id: &str) -> Result<(), HookmanError> {
+        let valid = !id.is_empty()
+            && !id.starts_with('-')
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c));
+        if !valid {
+            return Err(HookmanError::InvalidCommand(
+                id.to_string(),
+                "IDs may only contain letters, digits, '-', '_' and '.', and can't start with '-'"
+                    .to_string(),
+            ));
+        }
+        if id == CHAINED_ID {
+            return Err(HookmanError::InvalidCommand(
+                id.to_string(),
+                "this ID is reserved for a chained pre-existing hook".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The code to run: the `script` if set, otherwise the `command`.
+    pub fn body(&self) -> &str {
+        self.script.as_deref().unwrap_or(&self.command)
+    }
+
+    /// Checks the settings that can't be validated while parsing: that
+    /// exactly one of `command` and `script` is set, file and branch patterns
+    /// and the working directory.
+    pub fn validate(&self) -> Result<(), HookmanError> {
+        let reason = match (self.command.is_empty(), &self.script) {
+            (true, None) => Some("set 'command' or 'script'"),
+            (false, Some(_)) => Some("set either 'command' or 'script', not both"),
+            _ => None,
+        };
+        if let Some(reason) = reason {
+            return Err(HookmanError::InvalidCommand(
+                self.id.clone(),
+                reason.to_string(),
+            ));
+        }
+        self.file_filter()?;
+        if let Some(when) = &self.when {
+            when.validate()?;
         }
+        if let Some(cwd) = &self.cwd {
+            let inside = cwd
+                .components()
+                .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+            if !inside {
+                return Err(HookmanError::InvalidCwd(
+                    cwd.display().to_string(),
+                    self.id.clone(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The command's file patterns, or `None` if it runs regardless of which
+    /// files changed.
+    pub fn file_filter(&self) -> Result<Option<FileFilter>, HookmanError> {
+        if self.files.is_empty() && self.exclude.is_empty() {
+            return Ok(None);
+        }
+        FileFilter::new(&self.files, &self.exclude).map(Some)
     }
 }
 
@@ -43,6 +184,97 @@ mod tests {
         assert_eq!(cmd.description, None);
     }
 
+    #[test]
+    fn test_command_timeout_deserialize() {
+        let cmd: Command =
+            toml::from_str("id = \"test\"\ncommand = \"cargo test\"\ntimeout = \"5m\"\n").unwrap();
+
+        assert_eq!(cmd.timeout, Some("5m".parse().unwrap()));
+        assert_eq!(cmd.description, None);
+    }
+
+    #[test]
+    fn test_command_enabled_by_default() {
+        let mut cmd: Command = toml::from_str("id = \"test\"\ncommand = \"true\"\n").unwrap();
+        assert!(cmd.enabled);
+        assert!(!toml::to_string(&cmd).unwrap().contains("enabled"));
+
+        cmd.enabled = false;
+        let serialized = toml::to_string(&cmd).unwrap();
+        assert!(serialized.contains("enabled = false"));
+        assert!(!toml::from_str::<Command>(&serialized).unwrap().enabled);
+    }
+
+    #[test]
+    fn test_command_env_and_cwd_deserialize() {
+        let cmd: Command = toml::from_str(
+            "id = \"test\"\ncommand = \"npm test\"\ncwd = \"frontend\"\nenv = { NODE_ENV = \"test\" }\n",
+        )
+        .unwrap();
+
+        assert_eq!(cmd.cwd, Some(PathBuf::from("frontend")));
+        assert_eq!(cmd.env.get("NODE_ENV").map(String::as_str), Some("test"));
+        assert!(cmd.validate().is_ok());
+    }
+
+    #[test]
+    fn test_command_script_deserialize() {
+        let cmd: Command = toml::from_str(
+            "id = \"check\"\nshell = \"python3\"\nscript = \"\"\"\nimport sys\nsys.exit(0)\n\"\"\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(cmd.body(), "import sys\nsys.exit(0)\n");
+        assert_eq!(cmd.shell.as_deref(), Some("python3"));
+        assert!(cmd.validate().is_ok());
+
+        let serialized = toml::to_string(&cmd).unwrap();
+        assert!(!serialized.contains("command"));
+        assert_eq!(toml::from_str::<Command>(&serialized).unwrap(), cmd);
+    }
+
+    #[test]
+    fn test_validate_requires_command_or_script() {
+        let mut cmd = Command::new("test".to_string(), String::new(), None);
+        assert!(matches!(
+            cmd.validate(),
+            Err(HookmanError::InvalidCommand(..))
+        ));
+
+        cmd.command = "true".to_string();
+        cmd.script = Some("true\n".to_string());
+        assert!(matches!(
+            cmd.validate(),
+            Err(HookmanError::InvalidCommand(..))
+        ));
+    }
+
+    #[test]
+    fn test_validate_id() {
+        for id in ["format", "cargo-test", "lint_js", "v1.2"] {
+            assert!(Command::validate_id(id).is_ok(), "{}", id);
+        }
+        for id in [
+            "", "-x", "a b", "a,b", "it's", "$(id)", "a\"b", "a`b`", "a\nb", "日本", "chained",
+        ] {
+            assert!(Command::validate_id(id).is_err(), "{:?}", id);
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_cwd_outside_repository() {
+        for cwd in ["/tmp", "../other", "frontend/../.."] {
+            let mut cmd = Command::new("test".to_string(), "true".to_string(), None);
+            cmd.cwd = Some(PathBuf::from(cwd));
+
+            assert!(
+                matches!(cmd.validate(), Err(HookmanError::InvalidCwd(..))),
+                "{}",
+                cwd
+            );
+        }
+    }
+
     #[test]
     fn test_command_serialize_deserialize() {
         let cmd = Command::new(
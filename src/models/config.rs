@@ -3,12 +3,16 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub version: String,
+    /// Default interpreter for commands whose hook doesn't set one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             version: env!("CARGO_PKG_VERSION").to_string(),
+            shell: None,
         }
     }
 }
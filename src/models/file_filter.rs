@@ -0,0 +1,121 @@
+use crate::error::HookmanError;
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+
+/// The compiled `files` and `exclude` patterns of a command.
+///
+/// Patterns without a `/` match the file name in any directory, so `*.rs`
+/// matches `src/main.rs`; patterns with a `/` match the whole path relative
+/// to the repository root, with `**` spanning directories.
+#[derive(Debug, Clone)]
+pub struct FileFilter {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+impl FileFilter {
+    pub fn new(files: &[String], exclude: &[String]) -> Result<Self, HookmanError> {
+        Ok(FileFilter {
+            include: if files.is_empty() {
+                None
+            } else {
+                Some(build(files)?)
+            },
+            exclude: build(exclude)?,
+        })
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        self.include.as_ref().is_none_or(|set| set.is_match(path)) && !self.exclude.is_match(path)
+    }
+
+    /// The paths from `paths` that pass the filter, in their original order.
+    pub fn filter(&self, paths: &[String]) -> Vec<String> {
+        paths
+            .iter()
+            .filter(|path| self.matches(path))
+            .cloned()
+            .collect()
+    }
+}
+
+fn build(patterns: &[String]) -> Result<GlobSet, HookmanError> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(compile(pattern)?);
+    }
+    builder
+        .build()
+        .map_err(|e| HookmanError::InvalidPattern(patterns.join(", "), e.to_string()))
+}
+
+fn compile(pattern: &str) -> Result<Glob, HookmanError> {
+    let anchored = match pattern.strip_prefix('/') {
+        Some(rooted) => rooted.to_string(),
+        None if pattern.contains('/') => pattern.to_string(),
+        None => format!("**/{}", pattern),
+    };
+
+    GlobBuilder::new(&anchored)
+        .literal_separator(true)
+        .build()
+        .map_err(|e| HookmanError::InvalidPattern(pattern.to_string(), e.kind().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(files: &[&str], exclude: &[&str]) -> FileFilter {
+        let strings =
+            |patterns: &[&str]| patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+        FileFilter::new(&strings(files), &strings(exclude)).unwrap()
+    }
+
+    #[test]
+    fn test_pattern_without_slash_matches_any_directory() {
+        let filter = filter(&["*.rs", "Cargo.toml"], &[]);
+
+        assert!(filter.matches("main.rs"));
+        assert!(filter.matches("src/models/hook.rs"));
+        assert!(filter.matches("crates/core/Cargo.toml"));
+        assert!(!filter.matches("README.md"));
+    }
+
+    #[test]
+    fn test_pattern_with_slash_matches_from_root() {
+        let filter = filter(&["src/**/*.ts", "/package.json"], &[]);
+
+        assert!(filter.matches("src/app.ts"));
+        assert!(filter.matches("src/components/button.ts"));
+        assert!(!filter.matches("test/src/app.ts"));
+        assert!(filter.matches("package.json"));
+        assert!(!filter.matches("frontend/package.json"));
+    }
+
+    #[test]
+    fn test_exclude_wins_over_files() {
+        let filter = filter(&["*.rs"], &["tests/**"]);
+
+        assert!(filter.matches("src/lib.rs"));
+        assert!(!filter.matches("tests/run_test.rs"));
+    }
+
+    #[test]
+    fn test_exclude_only_matches_everything_else() {
+        let filter = filter(&[], &["*.md"]);
+
+        assert!(filter.matches("src/lib.rs"));
+        assert!(!filter.matches("docs/USAGE.md"));
+        assert_eq!(
+            filter.filter(&["a.md".to_string(), "b.rs".to_string()]),
+            vec!["b.rs".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let err = FileFilter::new(&["src/[".to_string()], &[]).unwrap_err();
+
+        assert!(err.to_string().starts_with("Invalid file pattern 'src/['"));
+    }
+}
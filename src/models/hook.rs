@@ -1,6 +1,9 @@
-use crate::models::Command;
+use crate::error::HookmanError;
+use crate::models::{Command, Timeout};
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
 use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum, Default)]
@@ -47,6 +50,47 @@ impl HookType {
         }
     }
 
+    /// Names of the positional arguments Git passes to this hook. Each one is
+    /// exposed to commands as a `HOOKMAN_<NAME>` environment variable, in
+    /// addition to the usual `$1`, `$2`, ... positional parameters.
+    pub fn arg_names(&self) -> &'static [&'static str] {
+        match self {
+            HookType::PrePush => &["REMOTE_NAME", "REMOTE_URL"],
+            HookType::CommitMsg => &["COMMIT_MSG_FILE"],
+            HookType::PreRebase => &["UPSTREAM", "BRANCH"],
+            HookType::PostCheckout => &["PREV_HEAD", "NEW_HEAD", "CHECKOUT_TYPE"],
+            HookType::PostMerge => &["SQUASH"],
+            HookType::Update => &["REF_NAME", "OLD_REV", "NEW_REV"],
+            HookType::PrepareCommitMsg => &["COMMIT_MSG_FILE", "COMMIT_SOURCE", "COMMIT_SHA"],
+            HookType::PostRewrite => &["REWRITE_COMMAND"],
+            HookType::PreCommit
+            | HookType::PostCommit
+            | HookType::PreReceive
+            | HookType::PostReceive
+            | HookType::PostUpdate
+            | HookType::PreApplyPatch
+            | HookType::PostApplyPatch
+            | HookType::PreMerge => &[],
+        }
+    }
+
+    /// Whether the hook has a set of changed files for `files` patterns and
+    /// `{files}`: the staged files in pre-commit, the pushed ones in pre-push.
+    pub fn has_changed_files(&self) -> bool {
+        matches!(self, HookType::PreCommit | HookType::PrePush)
+    }
+
+    /// Whether Git feeds data to this hook on standard input.
+    pub fn reads_stdin(&self) -> bool {
+        matches!(
+            self,
+            HookType::PrePush
+                | HookType::PreReceive
+                | HookType::PostReceive
+                | HookType::PostRewrite
+        )
+    }
+
     pub fn all() -> Vec<HookType> {
         vec![
             HookType::PreCommit,
@@ -101,20 +145,223 @@ impl FromStr for HookType {
     }
 }
 
+/// When to run a pre-existing, non-hookman hook relative to the configured
+/// commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChainMode {
+    Before,
+    After,
+}
+
+impl std::fmt::Display for ChainMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChainMode::Before => write!(f, "before"),
+            ChainMode::After => write!(f, "after"),
+        }
+    }
+}
+
+/// What pre-commit does with staged files that a `fixer` command modified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FixMode {
+    /// Add the fixed files to the index and carry on.
+    #[default]
+    Restage,
+    /// Fail the hook so the fixes can be reviewed and staged by hand.
+    Fail,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Hook {
     #[serde(skip_deserializing)]
     pub hook_type: HookType,
+    /// Keep running the hook that was installed before hookman, before or
+    /// after the configured commands.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain: Option<ChainMode>,
+    /// Default timeout for commands that don't set their own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<Timeout>,
+    /// Stop at the first failing command. When false, every command runs and
+    /// all failures are reported together.
+    #[serde(default = "default_fail_fast", skip_serializing_if = "is_true")]
+    pub fail_fast: bool,
+    /// Run the commands concurrently, printing each one's output in
+    /// configuration order once it finishes.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub parallel: bool,
+    /// Maximum number of commands running at once in parallel mode. Defaults
+    /// to the number of available CPUs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<NonZeroUsize>,
+    /// In pre-commit, set unstaged changes and untracked files aside while the
+    /// commands run, so they only see what is being committed.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stash_unstaged: bool,
+    /// What to do when a `fixer` command modifies staged files. Defaults to
+    /// re-staging them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix_mode: Option<FixMode>,
+    /// Environment variables set for every command. A command's own `env`
+    /// takes precedence.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Default interpreter for commands that don't set their own, falling
+    /// back to the `shell` in config.toml and then `sh`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+    #[serde(default)]
     pub commands: Vec<Command>,
 }
 
+fn default_fail_fast() -> bool {
+    true
+}
+
+pub(super) fn is_true(value: &bool) -> bool {
+    *value
+}
+
 impl Hook {
     pub fn new(hook_type: HookType) -> Self {
         Hook {
             hook_type,
+            chain: None,
+            timeout: None,
+            fail_fast: true,
+            parallel: false,
+            concurrency: None,
+            stash_unstaged: false,
+            fix_mode: None,
+            env: BTreeMap::new(),
+            shell: None,
             commands: Vec::new(),
         }
     }
+
+    fn position(&self, id: &str) -> Option<usize> {
+        self.commands.iter().position(|c| c.id == id)
+    }
+
+    /// Checks each command's settings, that every `depends_on` entry names a
+    /// command of this hook and that the dependencies don't form a cycle.
+    pub fn validate(&self) -> Result<(), HookmanError> {
+        for command in &self.commands {
+            command.validate()?;
+
+            if !self.hook_type.has_changed_files() && command.body().contains("{files}") {
+                return Err(HookmanError::InvalidCommand(
+                    command.id.clone(),
+                    format!(
+                        "{{files}} is only available in pre-commit and pre-push hooks, not {}",
+                        self.hook_type
+                    ),
+                ));
+            }
+
+            if let Some(unknown) = command
+                .depends_on
+                .iter()
+                .find(|dep| self.position(dep).is_none())
+            {
+                return Err(HookmanError::UnknownDependency(
+                    command.id.clone(),
+                    unknown.clone(),
+                    self.hook_type.to_string(),
+                ));
+            }
+        }
+
+        let mut marks = vec![Mark::Unvisited; self.commands.len()];
+        let mut path = Vec::new();
+        for index in 0..self.commands.len() {
+            if let Some(cycle) = self.find_cycle(index, &mut marks, &mut path) {
+                return Err(HookmanError::DependencyCycle(
+                    cycle.join(" -> "),
+                    self.hook_type.to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Depth-first search for a cycle through `index`, returning the IDs
+    /// along it with the first repeated at the end.
+    fn find_cycle(
+        &self,
+        index: usize,
+        marks: &mut [Mark],
+        path: &mut Vec<usize>,
+    ) -> Option<Vec<String>> {
+        match marks[index] {
+            Mark::Done => return None,
+            Mark::OnPath => {
+                let start = path.iter().position(|&i| i == index).unwrap_or(0);
+                return Some(
+                    path[start..]
+                        .iter()
+                        .chain(std::iter::once(&index))
+                        .map(|&i| self.commands[i].id.clone())
+                        .collect(),
+                );
+            }
+            Mark::Unvisited => {}
+        }
+
+        marks[index] = Mark::OnPath;
+        path.push(index);
+        for dep in &self.commands[index].depends_on {
+            if let Some(dep) = self.position(dep) {
+                if let Some(cycle) = self.find_cycle(dep, marks, path) {
+                    return Some(cycle);
+                }
+            }
+        }
+        path.pop();
+        marks[index] = Mark::Done;
+
+        None
+    }
+
+    /// Indices of the commands in the order they run: every command after
+    /// the commands it depends on, and otherwise in configuration order.
+    pub fn execution_order(&self) -> Vec<usize> {
+        let mut placed = vec![false; self.commands.len()];
+        let mut order = Vec::with_capacity(self.commands.len());
+
+        while order.len() < self.commands.len() {
+            let next = (0..self.commands.len()).find(|&index| {
+                !placed[index]
+                    && self.commands[index]
+                        .depends_on
+                        .iter()
+                        .all(|dep| self.position(dep).is_none_or(|dep| placed[dep]))
+            });
+            match next {
+                Some(index) => {
+                    placed[index] = true;
+                    order.push(index);
+                }
+                // Only reachable with a cycle, which `validate` rejects.
+                None => {
+                    order.extend((0..self.commands.len()).filter(|&index| !placed[index]));
+                }
+            }
+        }
+
+        order
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mark {
+    Unvisited,
+    OnPath,
+    Done,
 }
 
 #[cfg(test)]
@@ -160,10 +407,147 @@ mod tests {
         assert!(all_hooks.contains(&HookType::PostRewrite));
     }
 
+    #[test]
+    fn test_hook_type_arg_names() {
+        assert_eq!(HookType::CommitMsg.arg_names(), &["COMMIT_MSG_FILE"]);
+        assert_eq!(
+            HookType::PrePush.arg_names(),
+            &["REMOTE_NAME", "REMOTE_URL"]
+        );
+        assert!(HookType::PreCommit.arg_names().is_empty());
+    }
+
+    #[test]
+    fn test_hook_type_has_changed_files() {
+        assert!(HookType::PreCommit.has_changed_files());
+        assert!(HookType::PrePush.has_changed_files());
+        assert!(!HookType::PostCheckout.has_changed_files());
+    }
+
+    #[test]
+    fn test_hook_type_reads_stdin() {
+        assert!(HookType::PrePush.reads_stdin());
+        assert!(HookType::PostRewrite.reads_stdin());
+        assert!(!HookType::PreCommit.reads_stdin());
+        assert!(!HookType::CommitMsg.reads_stdin());
+    }
+
     #[test]
     fn test_hook_new() {
         let hook = Hook::new(HookType::PreCommit);
         assert_eq!(hook.hook_type, HookType::PreCommit);
+        assert_eq!(hook.chain, None);
+        assert!(hook.commands.is_empty());
+    }
+
+    #[test]
+    fn test_hook_chain_deserialize() {
+        let hook: Hook = toml::from_str("chain = \"after\"\n").unwrap();
+        assert_eq!(hook.chain, Some(ChainMode::After));
         assert!(hook.commands.is_empty());
+
+        let hook: Hook = toml::from_str("commands = []\n").unwrap();
+        assert_eq!(hook.chain, None);
+        assert!(hook.fail_fast);
+
+        assert!(toml::from_str::<Hook>("chain = \"sideways\"\n").is_err());
+    }
+
+    #[test]
+    fn test_hook_chain_not_serialized_when_unset() {
+        let hook = Hook::new(HookType::PrePush);
+        let serialized = toml::to_string(&hook).unwrap();
+        assert!(!serialized.contains("chain"));
+        assert!(!serialized.contains("parallel"));
+    }
+
+    fn hook_with(commands: &[(&str, &[&str])]) -> Hook {
+        let mut hook = Hook::new(HookType::PreCommit);
+        for (id, depends_on) in commands {
+            let mut command = Command::new(id.to_string(), "true".to_string(), None);
+            command.depends_on = depends_on.iter().map(|dep| dep.to_string()).collect();
+            hook.commands.push(command);
+        }
+        hook
+    }
+
+    #[test]
+    fn test_execution_order_follows_dependencies() {
+        let hook = hook_with(&[("test", &["build"]), ("lint", &[]), ("build", &[])]);
+
+        assert!(hook.validate().is_ok());
+        assert_eq!(hook.execution_order(), vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn test_execution_order_without_dependencies_is_config_order() {
+        let hook = hook_with(&[("a", &[]), ("b", &[]), ("c", &[])]);
+
+        assert_eq!(hook.execution_order(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_validate_rejects_files_placeholder_without_changed_files() {
+        let mut hook = Hook::new(HookType::PostCommit);
+        hook.commands.push(Command::new(
+            "lint".to_string(),
+            "eslint {files}".to_string(),
+            None,
+        ));
+
+        let err = hook.validate().unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Invalid command 'lint': {files} is only available in pre-commit and pre-push hooks, not post-commit"
+        );
+
+        hook.hook_type = HookType::PrePush;
+        assert!(hook.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_dependency() {
+        let hook = hook_with(&[("test", &["build"])]);
+
+        let err = hook.validate().unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Command 'test' in hook 'pre-commit' depends on unknown command 'build'"
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_cycles() {
+        let hook = hook_with(&[("a", &["c"]), ("b", &["a"]), ("c", &["b"])]);
+        let err = hook.validate().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Dependency cycle in hook 'pre-commit': a -> c -> b -> a"
+        );
+
+        let hook = hook_with(&[("self", &["self"])]);
+        assert!(hook.validate().is_err());
+    }
+
+    #[test]
+    fn test_hook_fix_mode_deserialize() {
+        let hook: Hook = toml::from_str("fix_mode = \"fail\"\n").unwrap();
+        assert_eq!(hook.fix_mode, Some(FixMode::Fail));
+
+        let hook: Hook = toml::from_str("").unwrap();
+        assert_eq!(hook.fix_mode.unwrap_or_default(), FixMode::Restage);
+
+        assert!(toml::from_str::<Hook>("fix_mode = \"ignore\"\n").is_err());
+    }
+
+    #[test]
+    fn test_hook_parallel_deserialize() {
+        let hook: Hook = toml::from_str("parallel = true\nconcurrency = 2\n").unwrap();
+        assert!(hook.parallel);
+        assert_eq!(hook.concurrency, NonZeroUsize::new(2));
+
+        assert!(toml::from_str::<Hook>("parallel = true\nconcurrency = 0\n").is_err());
     }
 }
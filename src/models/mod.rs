@@ -1,7 +1,13 @@
 mod command;
 mod config;
+mod file_filter;
 mod hook;
+mod timeout;
+mod when;
 
-pub use command::Command;
+pub use command::{Command, CHAINED_ID};
 pub use config::Config;
-pub use hook::{Hook, HookType};
+pub use file_filter::FileFilter;
+pub use hook::{ChainMode, FixMode, Hook, HookType};
+pub use timeout::Timeout;
+pub use when::{is_ci, CheckoutType, Situation, When};
@@ -0,0 +1,190 @@
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// A command timeout, written in configuration as a human-readable duration
+/// such as `"90s"`, `"5m"`, `"1h30m"` or `"500ms"`. A bare number is taken as
+/// seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeout(Duration);
+
+impl Timeout {
+    pub fn duration(&self) -> Duration {
+        self.0
+    }
+}
+
+impl FromStr for Timeout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim();
+        if input.is_empty() {
+            return Err("Timeout cannot be empty".to_string());
+        }
+
+        if let Ok(secs) = input.parse::<u64>() {
+            return Ok(Timeout(Duration::from_secs(secs)));
+        }
+
+        let mut total = Duration::ZERO;
+        let mut rest = input;
+        while !rest.is_empty() {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            if digits == 0 {
+                return Err(format!("Invalid timeout '{}'", s));
+            }
+            let value: u64 = rest[..digits]
+                .parse()
+                .map_err(|_| format!("Invalid timeout '{}'", s))?;
+            rest = &rest[digits..];
+
+            let unit_len = rest
+                .find(|c: char| c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let too_long = || format!("Timeout '{}' is too long", s);
+            let unit = match &rest[..unit_len] {
+                "ms" => Duration::from_millis(value),
+                "s" => Duration::from_secs(value),
+                "m" => Duration::from_secs(value.checked_mul(60).ok_or_else(too_long)?),
+                "h" => Duration::from_secs(value.checked_mul(3600).ok_or_else(too_long)?),
+                other => {
+                    return Err(format!(
+                        "Invalid timeout unit '{}' in '{}' (use ms, s, m or h)",
+                        other, s
+                    ))
+                }
+            };
+            total = total.checked_add(unit).ok_or_else(too_long)?;
+            rest = &rest[unit_len..];
+        }
+
+        if total.is_zero() {
+            return Err(format!("Timeout '{}' must be greater than zero", s));
+        }
+
+        Ok(Timeout(total))
+    }
+}
+
+impl fmt::Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let millis = self.0.as_millis();
+        if !millis.is_multiple_of(1000) {
+            return write!(f, "{}ms", millis);
+        }
+
+        let secs = self.0.as_secs();
+        let (hours, minutes, seconds) = (secs / 3600, (secs / 60) % 60, secs % 60);
+        if hours > 0 {
+            write!(f, "{}h", hours)?;
+        }
+        if minutes > 0 {
+            write!(f, "{}m", minutes)?;
+        }
+        if seconds > 0 || secs == 0 {
+            write!(f, "{}s", seconds)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Timeout {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Timeout {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TimeoutVisitor;
+
+        impl de::Visitor<'_> for TimeoutVisitor {
+            type Value = Timeout;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a duration such as \"90s\" or \"5m\", or a number of seconds")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Timeout, E> {
+                value.parse().map_err(E::custom)
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Timeout, E> {
+                if value <= 0 {
+                    return Err(E::custom("timeout must be greater than zero"));
+                }
+                Ok(Timeout(Duration::from_secs(value as u64)))
+            }
+        }
+
+        deserializer.deserialize_any(TimeoutVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_units() {
+        assert_eq!("90s".parse(), Ok(Timeout(Duration::from_secs(90))));
+        assert_eq!("5m".parse(), Ok(Timeout(Duration::from_secs(300))));
+        assert_eq!("2h".parse(), Ok(Timeout(Duration::from_secs(7200))));
+        assert_eq!("500ms".parse(), Ok(Timeout(Duration::from_millis(500))));
+        assert_eq!("1m30s".parse(), Ok(Timeout(Duration::from_secs(90))));
+        assert_eq!("45".parse(), Ok(Timeout(Duration::from_secs(45))));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!("".parse::<Timeout>().is_err());
+        assert!("abc".parse::<Timeout>().is_err());
+        assert!("10d".parse::<Timeout>().is_err());
+        assert!("0s".parse::<Timeout>().is_err());
+        assert!("s".parse::<Timeout>().is_err());
+    }
+
+    #[test]
+    fn test_parse_overflow() {
+        let max = u64::MAX;
+        for input in [
+            format!("{}m", max),
+            format!("{}h", max / 60),
+            format!("{}s1s", max),
+        ] {
+            assert_eq!(
+                input.parse::<Timeout>(),
+                Err(format!("Timeout '{}' is too long", input))
+            );
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Timeout(Duration::from_secs(90)).to_string(), "1m30s");
+        assert_eq!(Timeout(Duration::from_secs(300)).to_string(), "5m");
+        assert_eq!(Timeout(Duration::from_secs(3661)).to_string(), "1h1m1s");
+        assert_eq!(Timeout(Duration::from_millis(1500)).to_string(), "1500ms");
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Wrapper {
+            timeout: Timeout,
+        }
+
+        let wrapper: Wrapper = toml::from_str("timeout = \"90s\"").unwrap();
+        assert_eq!(wrapper.timeout, Timeout(Duration::from_secs(90)));
+        assert_eq!(toml::to_string(&wrapper).unwrap(), "timeout = \"1m30s\"\n");
+
+        let wrapper: Wrapper = toml::from_str("timeout = 30").unwrap();
+        assert_eq!(wrapper.timeout, Timeout(Duration::from_secs(30)));
+
+        assert!(toml::from_str::<Wrapper>("timeout = \"soon\"").is_err());
+    }
+}
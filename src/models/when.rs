@@ -0,0 +1,248 @@
+use crate::error::HookmanError;
+use globset::{GlobBuilder, GlobMatcher};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Conditions a command's `when` block puts on running it. Every condition
+/// that is set must hold.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct When {
+    /// Branch name globs, such as `main` or `release/*`. In pre-push these
+    /// match the branches being pushed to, elsewhere the checked-out branch.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub branch: Vec<String>,
+    /// Only run when pushing to this remote.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+    /// Only run in CI (`true`) or only outside it (`false`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ci: Option<bool>,
+    /// Environment variables that must have these values.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// In post-checkout, only run for branch or for file checkouts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkout_type: Option<CheckoutType>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CheckoutType {
+    Branch,
+    File,
+}
+
+impl std::fmt::Display for CheckoutType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckoutType::Branch => write!(f, "branch"),
+            CheckoutType::File => write!(f, "file"),
+        }
+    }
+}
+
+/// The state of the hook invocation that `when` conditions are checked
+/// against.
+#[derive(Debug, Clone, Default)]
+pub struct Situation {
+    /// The branches being pushed to in pre-push, otherwise the checked-out
+    /// branch (none when HEAD is detached).
+    pub branches: Vec<String>,
+    /// The remote being pushed to, in pre-push.
+    pub remote: Option<String>,
+    pub ci: bool,
+    /// The kind of checkout, in post-checkout.
+    pub checkout_type: Option<CheckoutType>,
+}
+
+impl When {
+    /// Checks that the branch patterns are valid globs.
+    pub fn validate(&self) -> Result<(), HookmanError> {
+        for pattern in &self.branch {
+            branch_matcher(pattern)?;
+        }
+        Ok(())
+    }
+
+    /// Returns why the command should be skipped in `situation`, or `None`
+    /// if every condition holds. `env` looks up environment variables.
+    pub fn check(
+        &self,
+        situation: &Situation,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Option<String> {
+        if !self.branch.is_empty() {
+            let matchers: Vec<GlobMatcher> = self
+                .branch
+                .iter()
+                .filter_map(|pattern| branch_matcher(pattern).ok())
+                .collect();
+            let matched = situation
+                .branches
+                .iter()
+                .any(|branch| matchers.iter().any(|matcher| matcher.is_match(branch)));
+            if !matched {
+                return Some(format!("branch is not {}", self.branch.join(", ")));
+            }
+        }
+
+        if let Some(remote) = &self.remote {
+            if situation.remote.as_deref() != Some(remote.as_str()) {
+                return Some(format!("remote is not {}", remote));
+            }
+        }
+
+        match self.ci {
+            Some(true) if !situation.ci => return Some("not running in CI".to_string()),
+            Some(false) if situation.ci => return Some("running in CI".to_string()),
+            _ => {}
+        }
+
+        for (name, value) in &self.env {
+            if env(name).as_deref() != Some(value.as_str()) {
+                return Some(format!("{} is not {}", name, value));
+            }
+        }
+
+        if let Some(checkout_type) = self.checkout_type {
+            if situation.checkout_type != Some(checkout_type) {
+                return Some(format!("not a {} checkout", checkout_type));
+            }
+        }
+
+        None
+    }
+}
+
+/// Whether hookman is running under a CI service, going by the variables
+/// common CI services set.
+pub fn is_ci(env: impl Fn(&str) -> Option<String>) -> bool {
+    let set = |name: &str| {
+        env(name).is_some_and(|value| !value.is_empty() && value != "false" && value != "0")
+    };
+    [
+        "CI",
+        "CONTINUOUS_INTEGRATION",
+        "BUILD_NUMBER",
+        "RUN_ID",
+        "TF_BUILD",
+        "JENKINS_URL",
+    ]
+    .iter()
+    .any(|name| set(name))
+}
+
+fn branch_matcher(pattern: &str) -> Result<GlobMatcher, HookmanError> {
+    GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .map(|glob| glob.compile_matcher())
+        .map_err(|e| HookmanError::InvalidPattern(pattern.to_string(), e.kind().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    fn on_branch(branch: &str) -> Situation {
+        Situation {
+            branches: vec![branch.to_string()],
+            ..Situation::default()
+        }
+    }
+
+    #[test]
+    fn test_empty_when_always_matches() {
+        assert_eq!(When::default().check(&Situation::default(), no_env), None);
+    }
+
+    #[test]
+    fn test_branch_globs() {
+        let when = When {
+            branch: vec!["main".to_string(), "release/*".to_string()],
+            ..When::default()
+        };
+
+        assert_eq!(when.check(&on_branch("main"), no_env), None);
+        assert_eq!(when.check(&on_branch("release/1.2"), no_env), None);
+        assert_eq!(
+            when.check(&on_branch("feature/x"), no_env),
+            Some("branch is not main, release/*".to_string())
+        );
+        assert!(when.check(&Situation::default(), no_env).is_some());
+    }
+
+    #[test]
+    fn test_remote_and_checkout_type() {
+        let when = When {
+            remote: Some("origin".to_string()),
+            ..When::default()
+        };
+        let pushing = |remote: &str| Situation {
+            remote: Some(remote.to_string()),
+            ..Situation::default()
+        };
+        assert_eq!(when.check(&pushing("origin"), no_env), None);
+        assert!(when.check(&pushing("upstream"), no_env).is_some());
+
+        let when = When {
+            checkout_type: Some(CheckoutType::Branch),
+            ..When::default()
+        };
+        let checkout = |checkout_type| Situation {
+            checkout_type: Some(checkout_type),
+            ..Situation::default()
+        };
+        assert_eq!(when.check(&checkout(CheckoutType::Branch), no_env), None);
+        assert_eq!(
+            when.check(&checkout(CheckoutType::File), no_env),
+            Some("not a branch checkout".to_string())
+        );
+    }
+
+    #[test]
+    fn test_ci_and_env() {
+        let when = When {
+            ci: Some(false),
+            env: BTreeMap::from([("DEPLOY".to_string(), "1".to_string())]),
+            ..When::default()
+        };
+        let env = |name: &str| (name == "DEPLOY").then(|| "1".to_string());
+
+        assert_eq!(when.check(&Situation::default(), env), None);
+        assert_eq!(
+            when.check(&Situation::default(), no_env),
+            Some("DEPLOY is not 1".to_string())
+        );
+        let in_ci = Situation {
+            ci: true,
+            ..Situation::default()
+        };
+        assert_eq!(when.check(&in_ci, env), Some("running in CI".to_string()));
+    }
+
+    #[test]
+    fn test_is_ci() {
+        assert!(is_ci(|name| (name == "CI").then(|| "true".to_string())));
+        assert!(!is_ci(|name| (name == "CI").then(|| "false".to_string())));
+        assert!(!is_ci(no_env));
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let when: When = toml::from_str(
+            "branch = [\"main\"]\nremote = \"origin\"\nci = false\ncheckout_type = \"branch\"\nenv = { VAR = \"value\" }\n",
+        )
+        .unwrap();
+
+        assert_eq!(when.branch, vec!["main".to_string()]);
+        assert_eq!(when.checkout_type, Some(CheckoutType::Branch));
+        assert_eq!(when.env.get("VAR").map(String::as_str), Some("value"));
+        assert!(toml::from_str::<When>("branches = [\"main\"]\n").is_err());
+    }
+}
@@ -0,0 +1,90 @@
+use crate::models::HookType;
+use anyhow::Result;
+use std::io::Read;
+use std::path::PathBuf;
+
+/// Everything Git handed to a hook invocation.
+#[derive(Debug, Clone)]
+pub struct HookContext {
+    pub hook_type: HookType,
+    pub args: Vec<String>,
+    pub stdin: Option<Vec<u8>>,
+    pub work_tree: PathBuf,
+}
+
+impl HookContext {
+    pub fn new(hook_type: HookType, args: Vec<String>, work_tree: PathBuf) -> Self {
+        HookContext {
+            hook_type,
+            args,
+            stdin: None,
+            work_tree,
+        }
+    }
+
+    /// Reads standard input once for hooks that receive data on it, so it can
+    /// be replayed to every command.
+    pub fn capture_stdin(mut self) -> Result<Self> {
+        if self.hook_type.reads_stdin() {
+            let mut buffer = Vec::new();
+            std::io::stdin().read_to_end(&mut buffer)?;
+            self.stdin = Some(buffer);
+        }
+        Ok(self)
+    }
+
+    /// Environment variables describing the hook invocation: `HOOKMAN_HOOK`
+    /// plus one `HOOKMAN_<NAME>` per positional argument of the hook type.
+    pub fn env_vars(&self) -> Vec<(String, String)> {
+        let mut vars = vec![("HOOKMAN_HOOK".to_string(), self.hook_type.to_string())];
+
+        for (index, name) in self.hook_type.arg_names().iter().enumerate() {
+            let value = self.args.get(index).cloned().unwrap_or_default();
+            vars.push((format!("HOOKMAN_{}", name), value));
+        }
+
+        vars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_vars_for_pre_push() {
+        let context = HookContext::new(
+            HookType::PrePush,
+            vec!["origin".to_string(), "git@example.com:repo.git".to_string()],
+            PathBuf::from("/repo"),
+        );
+
+        let vars = context.env_vars();
+
+        assert_eq!(
+            vars,
+            vec![
+                ("HOOKMAN_HOOK".to_string(), "pre-push".to_string()),
+                ("HOOKMAN_REMOTE_NAME".to_string(), "origin".to_string()),
+                (
+                    "HOOKMAN_REMOTE_URL".to_string(),
+                    "git@example.com:repo.git".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_env_vars_missing_arguments_are_empty() {
+        let context = HookContext::new(
+            HookType::PrepareCommitMsg,
+            vec![".git/COMMIT_EDITMSG".to_string()],
+            PathBuf::from("/repo"),
+        );
+
+        let vars = context.env_vars();
+
+        assert!(vars.contains(&("HOOKMAN_COMMIT_SOURCE".to_string(), String::new())));
+        assert!(vars.contains(&("HOOKMAN_COMMIT_SHA".to_string(), String::new())));
+    }
+}
@@ -0,0 +1,619 @@
+use crate::generator::shell_quote;
+use crate::models::{Command, Hook, Timeout};
+use crate::runner::{signal, HookContext};
+use anyhow::{bail, Result};
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Read, Write};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Child, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// How long a timed-out command gets to exit after SIGTERM before SIGKILL.
+const KILL_GRACE_PERIOD: Duration = Duration::from_secs(2);
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Interpreter for commands when neither they, their hook nor config.toml
+/// choose one.
+const DEFAULT_SHELL: &str = "sh";
+
+/// Shells that run code given with `-c`, followed by `$0` and the positional
+/// parameters. Other interpreters are handed the code in a file.
+const POSIX_SHELLS: &[&str] = &["sh", "bash", "zsh", "dash", "ksh", "mksh", "ash"];
+
+/// How a spawned command ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Exited(ExitStatus),
+    TimedOut(Timeout),
+}
+
+/// A configured command together with the settings it runs with.
+#[derive(Debug, Clone, Copy)]
+pub struct Invocation<'a> {
+    pub command: &'a Command,
+    pub timeout: Option<Timeout>,
+    /// Changed files selected for the command, substituted for `{files}` and
+    /// exported as `HOOKMAN_FILES`. `None` when the hook has no notion of
+    /// changed files.
+    pub files: Option<&'a [String]>,
+    /// The hook's environment variables, overridden by the command's own.
+    pub hook_env: &'a BTreeMap<String, String>,
+    /// The interpreter the command runs with.
+    pub shell: &'a str,
+}
+
+impl<'a> Invocation<'a> {
+    pub fn new(hook: &'a Hook, command: &'a Command, files: Option<&'a [String]>) -> Self {
+        Invocation {
+            command,
+            timeout: command.timeout.or(hook.timeout),
+            files,
+            hook_env: &hook.env,
+            shell: command
+                .shell
+                .as_deref()
+                .or(hook.shell.as_deref())
+                .unwrap_or(DEFAULT_SHELL),
+        }
+    }
+}
+
+/// Runs a single command through its interpreter, passing the hook arguments
+/// as positional parameters and replaying captured stdin.
+pub fn execute(invocation: &Invocation, context: &HookContext) -> Result<Outcome> {
+    let (process, _script) = prepare(invocation, context)?;
+    spawn(process, context, invocation.timeout)
+}
+
+/// Like [`execute`], but collects everything the command writes to stdout and
+/// stderr instead of passing it through, so concurrent commands don't
+/// interleave their output.
+pub fn execute_captured(
+    invocation: &Invocation,
+    context: &HookContext,
+) -> Result<(Outcome, Vec<u8>)> {
+    let (mut process, _script) = prepare(invocation, context)?;
+    // Both streams share one pipe so the buffer keeps their relative order.
+    let (mut reader, writer) = std::io::pipe()?;
+    process.stdout(writer.try_clone()?).stderr(writer);
+    let reader = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = reader.read_to_end(&mut output);
+        output
+    });
+
+    let outcome = spawn(process, context, invocation.timeout);
+    let output = reader.join().unwrap_or_default();
+    Ok((outcome?, output))
+}
+
+/// Runs an executable hook file directly, the way Git would, such as a
+/// pre-existing hook preserved by chain mode.
+pub fn execute_program(
+    path: &Path,
+    context: &HookContext,
+    timeout: Option<Timeout>,
+) -> Result<Outcome> {
+    let mut process = std::process::Command::new(path);
+    process.args(&context.args).current_dir(&context.work_tree);
+
+    spawn(process, context, timeout)
+}
+
+/// Builds the process for a command. Shells get the code with `-c` and the
+/// hook type as `$0`; any other interpreter is passed a temporary file holding
+/// the code, which lives as long as the returned [`ScriptFile`].
+fn prepare(
+    invocation: &Invocation,
+    context: &HookContext,
+) -> Result<(std::process::Command, Option<ScriptFile>)> {
+    let command = invocation.command;
+    let mut body = command.body().to_string();
+    let mut process = std::process::Command::new(invocation.shell);
+    let is_shell = is_posix_shell(invocation.shell);
+
+    let cwd = command.cwd.as_deref().unwrap_or(Path::new(""));
+    let dir = context.work_tree.join(cwd);
+    if !dir.is_dir() {
+        bail!("working directory {} does not exist", cwd.display());
+    }
+    process.current_dir(dir);
+
+    if let Some(files) = invocation.files {
+        // Paths are relative to the repository root; make them relative to
+        // where the command runs.
+        let files: Vec<String> = files.iter().map(|file| relative_to(file, cwd)).collect();
+        // Shell quoting means nothing to other interpreters, which can read
+        // HOOKMAN_FILES instead.
+        if is_shell {
+            let quoted: Vec<String> = files.iter().map(|file| shell_quote(file)).collect();
+            body = body.replace("{files}", &quoted.join(" "));
+        }
+        process.env("HOOKMAN_FILES", files.join("\n"));
+    }
+
+    process.envs(invocation.hook_env).envs(&command.env);
+
+    let script = if is_shell {
+        process.arg("-c").arg(body).arg(context.hook_type.as_str());
+        None
+    } else {
+        let script = ScriptFile::create(&body)?;
+        process.arg(&script.0);
+        Some(script)
+    };
+    process.args(&context.args);
+
+    Ok((process, script))
+}
+
+fn is_posix_shell(program: &str) -> bool {
+    Path::new(program)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| POSIX_SHELLS.contains(&name))
+}
+
+/// A command's code written out for an interpreter, removed when dropped.
+struct ScriptFile(PathBuf);
+
+impl ScriptFile {
+    fn create(body: &str) -> Result<Self> {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "hookman-{}-{}",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        // Readable by the interpreter, which runs as the same user, only.
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut file = options.open(&path)?;
+        let script = ScriptFile(path);
+        file.write_all(body.as_bytes())?;
+        Ok(script)
+    }
+}
+
+impl Drop for ScriptFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Rewrites a repository-relative `path` to be relative to `dir`, itself
+/// relative to the repository root.
+fn relative_to(path: &str, dir: &Path) -> String {
+    let dir: Vec<&str> = dir
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        })
+        .collect();
+    let path: Vec<&str> = path.split('/').collect();
+
+    let common = dir
+        .iter()
+        .zip(&path)
+        .take_while(|(a, b)| a == b)
+        .count()
+        .min(path.len() - 1);
+    let mut parts = vec![".."; dir.len() - common];
+    parts.extend(&path[common..]);
+    parts.join("/")
+}
+
+fn spawn(
+    mut process: std::process::Command,
+    context: &HookContext,
+    timeout: Option<Timeout>,
+) -> Result<Outcome> {
+    process.envs(context.env_vars());
+
+    if context.stdin.is_some() {
+        process.stdin(Stdio::piped());
+    }
+
+    // A separate process group lets a timeout kill everything the command
+    // started. It's only used when needed: a background group can't read
+    // from the terminal.
+    #[cfg(unix)]
+    if timeout.is_some() {
+        use std::os::unix::process::CommandExt;
+        process.process_group(0);
+    }
+
+    let spawned = process.spawn();
+    let program = process.get_program().to_string_lossy().into_owned();
+    // Drop our copies of any pipe ends handed to the child so readers see EOF
+    // once it exits.
+    drop(process);
+    let mut child = match spawned {
+        Err(e) if e.kind() == ErrorKind::NotFound => bail!("{} not found", program),
+        spawned => spawned?,
+    };
+
+    if let (Some(input), Some(mut stdin)) = (&context.stdin, child.stdin.take()) {
+        let input = input.clone();
+        // Commands are free to ignore stdin, so a closed pipe is not an error.
+        std::thread::spawn(move || {
+            let _ = stdin.write_all(&input);
+        });
+    }
+
+    match timeout {
+        Some(timeout) => wait_with_timeout(&mut child, timeout),
+        None => Ok(Outcome::Exited(child.wait()?)),
+    }
+}
+
+fn wait_with_timeout(child: &mut Child, timeout: Timeout) -> Result<Outcome> {
+    // A timeout too long to represent never expires.
+    let deadline = Instant::now().checked_add(timeout.duration());
+    let mut forwarded_interrupt = false;
+
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Outcome::Exited(status));
+        }
+
+        if signal::interrupted() && !forwarded_interrupt {
+            #[cfg(unix)]
+            signal_group(child, libc::SIGINT);
+            forwarded_interrupt = true;
+        }
+
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            terminate(child)?;
+            return Ok(Outcome::TimedOut(timeout));
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Stops a command and every process it started: SIGTERM to the group, then
+/// SIGKILL if it hasn't exited after a grace period.
+fn terminate(child: &mut Child) -> Result<()> {
+    #[cfg(unix)]
+    {
+        signal_group(child, libc::SIGTERM);
+        let deadline = Instant::now() + KILL_GRACE_PERIOD;
+        while Instant::now() < deadline {
+            if child.try_wait()?.is_some() {
+                // The leader is gone; make sure nothing it spawned lingers.
+                signal_group(child, libc::SIGKILL);
+                return Ok(());
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        signal_group(child, libc::SIGKILL);
+    }
+
+    #[cfg(not(unix))]
+    child.kill()?;
+
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn signal_group(child: &Child, signal: libc::c_int) {
+    // The child leads its own group, so its pid is the group id.
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), signal);
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::models::HookType;
+    use std::path::PathBuf;
+
+    fn command(script: &str) -> Command {
+        Command::new("test".to_string(), script.to_string(), None)
+    }
+
+    static NO_ENV: BTreeMap<String, String> = BTreeMap::new();
+
+    fn invocation(command: &Command) -> Invocation<'_> {
+        Invocation {
+            command,
+            timeout: None,
+            files: None,
+            hook_env: &NO_ENV,
+            shell: DEFAULT_SHELL,
+        }
+    }
+
+    fn exit_code(outcome: Outcome) -> Option<i32> {
+        match outcome {
+            Outcome::Exited(status) => status.code(),
+            Outcome::TimedOut(_) => None,
+        }
+    }
+
+    #[test]
+    fn test_execute_reports_exit_status() {
+        let context = HookContext::new(HookType::PreCommit, vec![], PathBuf::from("."));
+
+        assert_eq!(
+            exit_code(execute(&invocation(&command("true")), &context).unwrap()),
+            Some(0)
+        );
+        assert_eq!(
+            exit_code(execute(&invocation(&command("exit 3")), &context).unwrap()),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn test_execute_passes_arguments_and_stdin() {
+        let mut context = HookContext::new(
+            HookType::PrePush,
+            vec!["origin".to_string(), "url".to_string()],
+            PathBuf::from("."),
+        );
+        context.stdin = Some(b"refs/heads/main\n".to_vec());
+
+        let script = r#"test "$1" = origin && test "$HOOKMAN_REMOTE_URL" = url && read ref && test "$ref" = refs/heads/main"#;
+
+        assert_eq!(
+            exit_code(execute(&invocation(&command(script)), &context).unwrap()),
+            Some(0)
+        );
+        assert_eq!(
+            exit_code(execute(&invocation(&command(script)), &context).unwrap()),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_execute_captured_collects_output() {
+        let context = HookContext::new(HookType::PreCommit, vec![], PathBuf::from("."));
+
+        let (outcome, output) = execute_captured(
+            &invocation(&command("echo out; echo err >&2; exit 2")),
+            &context,
+        )
+        .unwrap();
+
+        assert_eq!(exit_code(outcome), Some(2));
+        assert_eq!(String::from_utf8_lossy(&output), "out\nerr\n");
+    }
+
+    #[test]
+    fn test_execute_substitutes_files() {
+        let context = HookContext::new(HookType::PreCommit, vec![], PathBuf::from("."));
+        let files = vec!["src/main.rs".to_string(), "it's here.rs".to_string()];
+        let command = command(
+            r#"set -- {files}; test "$#" = 2 && test "$2" = "it's here.rs" && test "$(printf '%s' "$HOOKMAN_FILES" | wc -l)" = 1"#,
+        );
+        let invocation = Invocation {
+            files: Some(&files),
+            ..invocation(&command)
+        };
+
+        assert_eq!(exit_code(execute(&invocation, &context).unwrap()), Some(0));
+    }
+
+    #[test]
+    fn test_execute_substitutes_hostile_file_names() {
+        let context = HookContext::new(HookType::PreCommit, vec![], PathBuf::from("."));
+        let files = crate::generator::quote::hostile_strings(200);
+        let command = command(r#"printf '%s\000' {files}"#);
+        let invocation = Invocation {
+            files: Some(&files),
+            ..invocation(&command)
+        };
+
+        let (outcome, output) = execute_captured(&invocation, &context).unwrap();
+
+        assert_eq!(exit_code(outcome), Some(0));
+        let printed: Vec<String> = output
+            .split(|&byte| byte == 0)
+            .map(|word| String::from_utf8(word.to_vec()).unwrap())
+            .collect();
+        assert_eq!(printed[..files.len()], files[..]);
+    }
+
+    #[test]
+    fn test_execute_applies_env_and_cwd() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join("frontend")).unwrap();
+        let context = HookContext::new(HookType::PreCommit, vec![], temp_dir.path().to_path_buf());
+        let hook_env = BTreeMap::from([
+            ("SHARED".to_string(), "hook".to_string()),
+            ("MODE".to_string(), "hook".to_string()),
+        ]);
+        let files = vec!["frontend/app.js".to_string(), "README.md".to_string()];
+
+        let mut command = command(
+            r#"test "$SHARED" = hook && test "$MODE" = command && test "$(basename "$PWD")" = frontend && test "$HOOKMAN_FILES" = "app.js
+../README.md""#,
+        );
+        command
+            .env
+            .insert("MODE".to_string(), "command".to_string());
+        command.cwd = Some("frontend".into());
+        let invocation = Invocation {
+            files: Some(&files),
+            hook_env: &hook_env,
+            ..invocation(&command)
+        };
+
+        assert_eq!(exit_code(execute(&invocation, &context).unwrap()), Some(0));
+    }
+
+    #[test]
+    fn test_execute_missing_cwd() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let context = HookContext::new(HookType::PreCommit, vec![], temp_dir.path().to_path_buf());
+        let mut command = command("true");
+        command.cwd = Some("missing".into());
+
+        let err = execute(&invocation(&command), &context).unwrap_err();
+
+        assert_eq!(err.to_string(), "working directory missing does not exist");
+    }
+
+    #[test]
+    fn test_execute_with_other_shells() {
+        let context = HookContext::new(
+            HookType::CommitMsg,
+            vec!["MSG".to_string()],
+            PathBuf::from("."),
+        );
+        let bash = command("[[ $0 == commit-msg && $1 == MSG ]]");
+        let bash = Invocation {
+            shell: "bash",
+            ..invocation(&bash)
+        };
+        assert_eq!(exit_code(execute(&bash, &context).unwrap()), Some(0));
+
+        let mut python = command("");
+        python.script =
+            Some("import sys\nsys.exit(0 if sys.argv[1:] == ['MSG'] else 1)\n".to_string());
+        let python = Invocation {
+            shell: "python3",
+            ..invocation(&python)
+        };
+        assert_eq!(exit_code(execute(&python, &context).unwrap()), Some(0));
+    }
+
+    #[test]
+    fn test_execute_missing_shell() {
+        let context = HookContext::new(HookType::PreCommit, vec![], PathBuf::from("."));
+        let command = command("true");
+        let invocation = Invocation {
+            shell: "no-such-interpreter",
+            ..invocation(&command)
+        };
+
+        let err = execute(&invocation, &context).unwrap_err();
+
+        assert_eq!(err.to_string(), "no-such-interpreter not found");
+    }
+
+    #[test]
+    fn test_is_posix_shell() {
+        assert!(is_posix_shell("sh"));
+        assert!(is_posix_shell("/usr/local/bin/bash"));
+        assert!(!is_posix_shell("python3"));
+        assert!(!is_posix_shell("/usr/bin/node"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_script_file_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let script = ScriptFile::create("print('hi')\n").unwrap();
+        let mode = std::fs::metadata(&script.0).unwrap().permissions().mode();
+
+        assert_eq!(mode & 0o777, 0o600);
+        let path = script.0.clone();
+        drop(script);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_relative_to() {
+        assert_eq!(relative_to("src/main.rs", Path::new("")), "src/main.rs");
+        assert_eq!(relative_to("web/app.js", Path::new("web")), "app.js");
+        assert_eq!(relative_to("web/app.js", Path::new("./web/")), "app.js");
+        assert_eq!(
+            relative_to("README.md", Path::new("web/ui")),
+            "../../README.md"
+        );
+        assert_eq!(
+            relative_to("web/api/x.js", Path::new("web/ui")),
+            "../api/x.js"
+        );
+        assert_eq!(relative_to("web", Path::new("web")), "../web");
+    }
+
+    #[test]
+    fn test_execute_program_passes_arguments_and_stdin() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let program = temp_dir.path().join("pre-push");
+        std::fs::write(
+            &program,
+            "#!/bin/sh\ntest \"$1\" = origin && read ref && test \"$ref\" = refs/heads/main\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut context = HookContext::new(
+            HookType::PrePush,
+            vec!["origin".to_string(), "url".to_string()],
+            PathBuf::from("."),
+        );
+        context.stdin = Some(b"refs/heads/main\n".to_vec());
+
+        assert_eq!(
+            exit_code(execute_program(&program, &context, None).unwrap()),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_execute_within_timeout() {
+        let context = HookContext::new(HookType::PreCommit, vec![], PathBuf::from("."));
+        let timeout = "5s".parse().unwrap();
+
+        let command = command("exit 4");
+        let invocation = Invocation {
+            timeout: Some(timeout),
+            ..invocation(&command)
+        };
+
+        let outcome = execute(&invocation, &context).unwrap();
+
+        assert_eq!(exit_code(outcome), Some(4));
+    }
+
+    #[test]
+    fn test_execute_timeout_kills_process_group() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let context = HookContext::new(HookType::PreCommit, vec![], temp_dir.path().to_path_buf());
+        let timeout = "200ms".parse().unwrap();
+
+        let command = command("sleep 30 & echo $! > bg.pid; sleep 30");
+        let invocation = Invocation {
+            timeout: Some(timeout),
+            ..invocation(&command)
+        };
+
+        let started = Instant::now();
+        let outcome = execute(&invocation, &context).unwrap();
+
+        assert_eq!(outcome, Outcome::TimedOut(timeout));
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        // Allow a moment for the orphaned process to be reaped.
+        let pid = std::fs::read_to_string(temp_dir.path().join("bg.pid")).unwrap();
+        let alive = || {
+            std::process::Command::new("kill")
+                .args(["-0", pid.trim()])
+                .status()
+                .unwrap()
+                .success()
+        };
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while alive() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        assert!(!alive(), "background process survived the timeout");
+    }
+}
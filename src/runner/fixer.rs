@@ -0,0 +1,126 @@
+use crate::git::changes;
+use crate::models::{Command, FixMode, Hook, HookType};
+use crate::runner::report::Status;
+use crate::runner::HookContext;
+use anyhow::Result;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// Watches the staged files of a pre-commit hook for changes made by `fixer`
+/// commands.
+pub struct FixTracker {
+    work_tree: PathBuf,
+    mode: FixMode,
+    staged: Vec<String>,
+    /// Staged files that also have unstaged changes. Re-adding them would
+    /// stage more than the fix, so they are never re-staged automatically.
+    partially_staged: Vec<String>,
+}
+
+/// Contents of the staged files before a fixer ran.
+pub struct Snapshot {
+    hashes: Vec<Option<u64>>,
+}
+
+impl FixTracker {
+    /// Returns `None` unless this is a pre-commit hook with fixer commands.
+    pub fn new(hook: &Hook, context: &HookContext) -> Result<Option<Self>> {
+        if hook.hook_type != HookType::PreCommit || !hook.commands.iter().any(|c| c.fixer) {
+            return Ok(None);
+        }
+
+        let staged = changes::staged_files(&context.work_tree)?;
+        let unstaged = changes::unstaged_files(&context.work_tree)?;
+        let partially_staged = staged
+            .iter()
+            .filter(|path| unstaged.contains(path))
+            .cloned()
+            .collect();
+
+        Ok(Some(FixTracker {
+            work_tree: context.work_tree.clone(),
+            mode: hook.fix_mode.unwrap_or_default(),
+            staged,
+            partially_staged,
+        }))
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            hashes: self
+                .staged
+                .iter()
+                .map(|path| hash_file(&self.work_tree.join(path)))
+                .collect(),
+        }
+    }
+
+    /// Deals with the staged files a passing fixer modified, turning `status`
+    /// into a failure if they need reviewing. Returns a note to show with the
+    /// command's output.
+    pub fn finish(
+        &self,
+        command: &Command,
+        before: &Snapshot,
+        status: &mut Status,
+    ) -> Option<String> {
+        if *status != Status::Passed {
+            return None;
+        }
+
+        let modified: Vec<String> = self
+            .staged
+            .iter()
+            .zip(&before.hashes)
+            .filter(|(path, hash)| hash_file(&self.work_tree.join(path)) != **hash)
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        let (restage, review): (Vec<String>, Vec<String>) = match self.mode {
+            FixMode::Restage => modified
+                .into_iter()
+                .partition(|path| !self.partially_staged.contains(path)),
+            FixMode::Fail => (Vec::new(), modified),
+        };
+
+        let mut note = None;
+        if !restage.is_empty() {
+            if let Err(e) = changes::stage(&self.work_tree, &restage) {
+                *status = failed(command, format!("could not re-stage fixes: {}", e));
+                return None;
+            }
+            note = Some(format!(
+                "  Re-staged files modified by {}: {}",
+                command.id,
+                restage.join(", ")
+            ));
+        }
+
+        if !review.is_empty() {
+            let reason = format!(
+                "files were modified by {}, review and re-stage: {}",
+                command.id,
+                review.join(", ")
+            );
+            *status = failed(command, reason);
+        }
+
+        note
+    }
+}
+
+fn failed(command: &Command, reason: String) -> Status {
+    Status::Failed {
+        reason,
+        exit_code: None,
+        allowed: command.allow_failure,
+    }
+}
+
+fn hash_file(path: &Path) -> Option<u64> {
+    let contents = std::fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    Some(hasher.finish())
+}
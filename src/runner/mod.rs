@@ -0,0 +1,499 @@
+mod context;
+mod executor;
+mod fixer;
+mod report;
+mod signal;
+
+pub use context::HookContext;
+
+use crate::error::HookmanError;
+use crate::git::stash::Stash;
+use crate::git::{changes, refs};
+use crate::models::{
+    is_ci, ChainMode, CheckoutType, Command, Hook, HookType, Situation, CHAINED_ID,
+};
+use anyhow::Result;
+use colored::Colorize;
+use executor::Invocation;
+use fixer::{FixTracker, Snapshot};
+use report::{RunReport, Status};
+use std::io::Write;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// Executes the commands of a hook in-process, as invoked by `hookman run`.
+pub struct HookRunner<'a> {
+    context: &'a HookContext,
+    chained: Option<PathBuf>,
+    stash_dir: Option<PathBuf>,
+    skipped: Vec<String>,
+}
+
+impl<'a> HookRunner<'a> {
+    pub fn new(context: &'a HookContext) -> Self {
+        HookRunner {
+            context,
+            chained: None,
+            stash_dir: None,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Sets the preserved pre-existing hook to run when the hook is in chain
+    /// mode.
+    pub fn with_chained(mut self, path: PathBuf) -> Self {
+        self.chained = Some(path);
+        self
+    }
+
+    /// Sets where unstaged changes are kept for hooks with `stash_unstaged`.
+    pub fn with_stash_dir(mut self, dir: PathBuf) -> Self {
+        self.stash_dir = Some(dir);
+        self
+    }
+
+    /// Sets the command IDs to skip, as listed in `HOOKMAN_SKIP`.
+    pub fn with_skipped(mut self, ids: Vec<String>) -> Self {
+        self.skipped = ids;
+        self
+    }
+
+    /// Runs every command, in order or concurrently with `parallel = true`.
+    /// By default the first failure stops the hook; with `fail_fast = false`
+    /// every command runs and the failures are collected. Either way a
+    /// summary is printed at the end.
+    pub fn run(&self, hook: &Hook) -> Result<()> {
+        let chained = match (hook.chain, &self.chained) {
+            (Some(mode), Some(path)) if path.is_file() => Some((mode, path.as_path())),
+            (Some(mode), _) => {
+                eprintln!(
+                    "{}",
+                    format!(
+                        "Warning: {} has chain = \"{}\", but no pre-existing hook was preserved; run 'hookman apply' to chain it",
+                        hook.hook_type, mode
+                    )
+                    .yellow()
+                );
+                None
+            }
+            _ => None,
+        };
+
+        if hook.commands.is_empty() && chained.is_none() {
+            return Ok(());
+        }
+
+        let plans = self.plan(hook)?;
+
+        signal::install();
+        println!("Running {} hooks...", hook.hook_type);
+
+        // Before stashing, which would hide which staged files also have
+        // unstaged changes.
+        let fixes = FixTracker::new(hook, self.context)?;
+        let mut stash = self.stash(hook)?;
+        let mut report = RunReport::default();
+
+        if let Some((ChainMode::Before, path)) = chained {
+            self.run_chained(path, hook, &mut report);
+        }
+
+        if hook.parallel {
+            self.run_parallel(hook, &plans, fixes.as_ref(), &mut report);
+        } else {
+            self.run_sequential(hook, &plans, fixes.as_ref(), &mut report);
+        }
+
+        if let Some((ChainMode::After, path)) = chained {
+            match stop_reason(hook, &report) {
+                Some(reason) => report.skip(CHAINED_ID, reason),
+                None => self.run_chained(path, hook, &mut report),
+            }
+        }
+
+        let restored = stash.as_mut().map_or(Ok(Vec::new()), Stash::restore);
+
+        report.print_summary();
+        let conflicts = restored?;
+
+        if signal::interrupted() {
+            eprintln!("{}", "✗ Interrupted".red());
+            return Err(HookmanError::Interrupted.into());
+        }
+
+        if !conflicts.is_empty() {
+            let error = HookmanError::StashConflict(conflicts.join(", "));
+            eprintln!("{}", format!("✗ {}", error).red());
+            if !report.has_required_failure() {
+                return Err(error.into());
+            }
+        }
+
+        if !report.has_required_failure() {
+            println!("All {} hooks passed!", hook.hook_type);
+        }
+
+        report.into_result()
+    }
+
+    /// Sets unstaged changes aside before a pre-commit hook with
+    /// `stash_unstaged`. They are restored when the stash is dropped, even if
+    /// something goes wrong along the way.
+    fn stash(&self, hook: &Hook) -> Result<Option<Stash>> {
+        match &self.stash_dir {
+            Some(dir) if hook.stash_unstaged && hook.hook_type == HookType::PreCommit => {
+                let stash = Stash::save(&self.context.work_tree, dir)?;
+                if stash.is_some() {
+                    println!("Stashed unstaged changes");
+                }
+                Ok(stash)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Decides up front which commands run, and with which changed files.
+    fn plan(&self, hook: &Hook) -> Result<Vec<Plan>> {
+        let needs_files = hook
+            .commands
+            .iter()
+            .any(|c| !c.files.is_empty() || !c.exclude.is_empty() || c.body().contains("{files}"));
+        let changed = if needs_files {
+            self.changed_files()?
+        } else {
+            None
+        };
+        let situation = if hook.commands.iter().any(|c| c.when.is_some()) {
+            self.situation()?
+        } else {
+            Situation::default()
+        };
+
+        hook.commands
+            .iter()
+            .map(|command| {
+                if !command.enabled {
+                    return Ok(Plan::Skip("disabled".to_string()));
+                }
+                if self.skipped.contains(&command.id) {
+                    return Ok(Plan::Skip("listed in HOOKMAN_SKIP".to_string()));
+                }
+                if let Some(reason) = command
+                    .when
+                    .as_ref()
+                    .and_then(|when| when.check(&situation, |name| std::env::var(name).ok()))
+                {
+                    return Ok(Plan::Skip(reason));
+                }
+
+                let filter = command.file_filter()?;
+                let files = match (&changed, &filter) {
+                    (None, _) => return Ok(Plan::Run(None)),
+                    (Some(changed), None) => changed.clone(),
+                    (Some(changed), Some(filter)) => filter.filter(changed),
+                };
+                // An empty {files} would make most tools check everything.
+                let plan = if files.is_empty()
+                    && (filter.is_some() || command.body().contains("{files}"))
+                {
+                    Plan::Skip("no matching files".to_string())
+                } else {
+                    Plan::Run(Some(files))
+                };
+                Ok(plan)
+            })
+            .collect()
+    }
+
+    /// The files this hook invocation is about: staged files for pre-commit
+    /// and pushed files for pre-push. Other hooks have none, and their
+    /// commands run regardless of file patterns.
+    fn changed_files(&self) -> Result<Option<Vec<String>>> {
+        let work_tree = &self.context.work_tree;
+        match self.context.hook_type {
+            HookType::PreCommit => changes::staged_files(work_tree).map(Some),
+            HookType::PrePush => {
+                let remote = self.context.args.first().map_or("", String::as_str);
+                let refs =
+                    String::from_utf8_lossy(self.context.stdin.as_deref().unwrap_or_default());
+                changes::pushed_files(work_tree, remote, &refs).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// What `when` conditions are checked against for this invocation.
+    fn situation(&self) -> Result<Situation> {
+        let context = self.context;
+        let arg = |index: usize| context.args.get(index).cloned();
+
+        let (branches, remote) = if context.hook_type == HookType::PrePush {
+            let refs = String::from_utf8_lossy(context.stdin.as_deref().unwrap_or_default());
+            (refs::pushed_branches(&refs), arg(0))
+        } else {
+            let branch = refs::current_branch(&context.work_tree)?;
+            (branch.into_iter().collect(), None)
+        };
+
+        let checkout_type = match (context.hook_type, arg(2).as_deref()) {
+            (HookType::PostCheckout, Some("1")) => Some(CheckoutType::Branch),
+            (HookType::PostCheckout, Some("0")) => Some(CheckoutType::File),
+            _ => None,
+        };
+
+        Ok(Situation {
+            branches,
+            remote,
+            ci: is_ci(|name| std::env::var(name).ok()),
+            checkout_type,
+        })
+    }
+
+    fn run_sequential(
+        &self,
+        hook: &Hook,
+        plans: &[Plan],
+        fixes: Option<&FixTracker>,
+        report: &mut RunReport,
+    ) {
+        for index in hook.execution_order() {
+            let command = &hook.commands[index];
+            let reason = plans[index]
+                .skip_reason()
+                .or_else(|| stop_reason(hook, report).map(str::to_string))
+                .or_else(|| blocked_reason(command, |id| report.status(id)));
+            if let Some(reason) = reason {
+                report.skip(&command.id, reason);
+                continue;
+            }
+
+            print_header(command);
+            let invocation = Invocation::new(hook, command, plans[index].files());
+            let snapshot = fixes.filter(|_| command.fixer).map(FixTracker::snapshot);
+            let started = Instant::now();
+            let result = executor::execute(&invocation, self.context);
+            let mut status = Status::of(&result, command.allow_failure);
+            if let (Some(fixes), Some(snapshot)) = (fixes, &snapshot) {
+                if let Some(note) = fixes.finish(command, snapshot, &mut status) {
+                    println!("{}", note);
+                }
+            }
+            report.record(&command.id, status, started.elapsed());
+        }
+    }
+
+    /// Runs the commands on up to `concurrency` threads. A command starts once
+    /// everything it depends on has passed; buffered output is printed in
+    /// execution order as soon as every earlier command has been printed.
+    ///
+    /// Fixers run alone: the changes a fixer made are whatever changed while
+    /// it ran, so nothing else may be running then.
+    fn run_parallel(
+        &self,
+        hook: &Hook,
+        plans: &[Plan],
+        fixes: Option<&FixTracker>,
+        report: &mut RunReport,
+    ) {
+        let order = hook.execution_order();
+        let limit = hook
+            .concurrency
+            .or_else(|| std::thread::available_parallelism().ok())
+            .map_or(1, NonZeroUsize::get);
+        let position = |id: &str| hook.commands.iter().position(|c| c.id == id);
+
+        // Per command index: the status once settled, and the completion
+        // waiting to be printed.
+        let mut statuses: Vec<Option<Status>> = vec![None; hook.commands.len()];
+        let mut completions: Vec<Option<Completion>> = hook.commands.iter().map(|_| None).collect();
+        let mut snapshots: Vec<Option<Snapshot>> = hook.commands.iter().map(|_| None).collect();
+        let mut started = vec![false; hook.commands.len()];
+        let mut running = 0;
+        let mut fixer_running = false;
+        let mut printed = 0;
+        let (sender, receiver) = mpsc::channel();
+
+        std::thread::scope(|scope| loop {
+            // Settle or start every command whose dependencies are settled, in
+            // execution order, as long as there is capacity. A fixer waiting
+            // for the running commands to finish holds back the ones after it.
+            let mut fixer_waiting = false;
+            for &index in &order {
+                let command = &hook.commands[index];
+                let ready = command
+                    .depends_on
+                    .iter()
+                    .all(|dep| position(dep).is_none_or(|dep| statuses[dep].is_some()));
+                if started[index] || !ready {
+                    continue;
+                }
+
+                // Same rule as sequential mode: nothing new starts once a
+                // required command has failed.
+                let required_failure = report.has_required_failure()
+                    || statuses
+                        .iter()
+                        .flatten()
+                        .any(|status| matches!(status, Status::Failed { allowed: false, .. }));
+                let reason = if let Some(reason) = plans[index].skip_reason() {
+                    Some(reason)
+                } else if signal::interrupted() {
+                    Some("interrupted".to_string())
+                } else if hook.fail_fast && required_failure {
+                    Some("earlier command failed".to_string())
+                } else {
+                    blocked_reason(command, |id| {
+                        position(id).and_then(|dep| statuses[dep].as_ref())
+                    })
+                };
+
+                if let Some(reason) = reason {
+                    started[index] = true;
+                    statuses[index] = Some(Status::Skipped(reason.clone()));
+                    completions[index] = Some(Completion::Skipped(reason));
+                } else if running < limit && !fixer_running && !fixer_waiting {
+                    let fixer = command.fixer && fixes.is_some();
+                    if fixer && running > 0 {
+                        fixer_waiting = true;
+                        continue;
+                    }
+                    started[index] = true;
+                    running += 1;
+                    if fixer {
+                        fixer_running = true;
+                        snapshots[index] = fixes.map(FixTracker::snapshot);
+                    }
+                    let sender = sender.clone();
+                    let invocation = Invocation::new(hook, command, plans[index].files());
+                    scope.spawn(move || {
+                        let started = Instant::now();
+                        let result = executor::execute_captured(&invocation, self.context);
+                        let _ = sender.send((index, result, started.elapsed()));
+                    });
+                }
+            }
+
+            while let Some(completion) = order
+                .get(printed)
+                .and_then(|&index| completions[index].take())
+            {
+                let command = &hook.commands[order[printed]];
+                match completion {
+                    Completion::Skipped(reason) => report.skip(&command.id, reason),
+                    Completion::Ran(status, output, elapsed) => {
+                        print_header(command);
+                        let mut stdout = std::io::stdout().lock();
+                        let _ = stdout.write_all(&output);
+                        let _ = stdout.flush();
+                        drop(stdout);
+                        report.record(&command.id, status, elapsed);
+                    }
+                }
+                printed += 1;
+            }
+
+            if running == 0 {
+                break;
+            }
+
+            let Ok((index, result, elapsed)) = receiver.recv() else {
+                break;
+            };
+            running -= 1;
+            let command = &hook.commands[index];
+            if snapshots[index].is_some() {
+                fixer_running = false;
+            }
+            let (result, mut output) = match result {
+                Ok((outcome, output)) => (Ok(outcome), output),
+                Err(e) => (Err(e), Vec::new()),
+            };
+            let mut status = Status::of(&result, command.allow_failure);
+            if let (Some(fixes), Some(snapshot)) = (fixes, &snapshots[index]) {
+                if let Some(note) = fixes.finish(command, snapshot, &mut status) {
+                    output.extend_from_slice(format!("{}\n", note).as_bytes());
+                }
+            }
+            statuses[index] = Some(status.clone());
+            completions[index] = Some(Completion::Ran(status, output, elapsed));
+        });
+    }
+
+    fn run_chained(&self, path: &Path, hook: &Hook, report: &mut RunReport) {
+        if self.skipped.iter().any(|id| id == CHAINED_ID) {
+            report.skip(CHAINED_ID, "listed in HOOKMAN_SKIP");
+            return;
+        }
+
+        println!(
+            "  → {}: existing {} hook",
+            CHAINED_ID, self.context.hook_type
+        );
+        let started = Instant::now();
+        let result = executor::execute_program(path, self.context, hook.timeout);
+        report.record(CHAINED_ID, Status::of(&result, false), started.elapsed());
+    }
+}
+
+/// Whether a command should run, decided before the hook starts.
+enum Plan {
+    /// Run with these changed files, if the hook has a notion of them.
+    Run(Option<Vec<String>>),
+    Skip(String),
+}
+
+impl Plan {
+    fn files(&self) -> Option<&[String]> {
+        match self {
+            Plan::Run(files) => files.as_deref(),
+            Plan::Skip(_) => None,
+        }
+    }
+
+    fn skip_reason(&self) -> Option<String> {
+        match self {
+            Plan::Run(_) => None,
+            Plan::Skip(reason) => Some(reason.clone()),
+        }
+    }
+}
+
+/// A command handled in parallel mode, waiting for its turn to be printed.
+enum Completion {
+    Ran(Status, Vec<u8>, Duration),
+    Skipped(String),
+}
+
+fn print_header(command: &Command) {
+    match &command.description {
+        Some(desc) => println!("  → {}: {}", command.id, desc),
+        None => println!("  → {}", command.id),
+    }
+}
+
+/// Why `command` can't run because of one of its dependencies, if it can't.
+/// `status` looks up the status of a command that has already been settled.
+fn blocked_reason<'s>(
+    command: &Command,
+    status: impl Fn(&str) -> Option<&'s Status>,
+) -> Option<String> {
+    command.depends_on.iter().find_map(|dep| match status(dep) {
+        Some(Status::Passed) => None,
+        Some(Status::Failed { .. }) => Some(format!("because {} failed", dep)),
+        Some(Status::Skipped(_)) => Some(format!("because {} was skipped", dep)),
+        None => Some(format!("because {} did not run", dep)),
+    })
+}
+
+/// Why the remaining commands should not run, if they shouldn't.
+fn stop_reason(hook: &Hook, report: &RunReport) -> Option<&'static str> {
+    if signal::interrupted() {
+        Some("interrupted")
+    } else if hook.fail_fast && report.has_required_failure() {
+        Some("earlier command failed")
+    } else {
+        None
+    }
+}
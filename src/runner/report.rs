@@ -0,0 +1,251 @@
+use crate::error::HookmanError;
+use crate::runner::executor::Outcome;
+use anyhow::Result;
+use colored::Colorize;
+use std::time::Duration;
+
+/// How a single command ended up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Passed,
+    Failed {
+        reason: String,
+        exit_code: Option<i32>,
+        allowed: bool,
+    },
+    Skipped(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct CommandResult {
+    pub id: String,
+    pub status: Status,
+    pub elapsed: Option<Duration>,
+}
+
+/// Results of every command in a hook run, in execution order.
+#[derive(Debug, Default)]
+pub struct RunReport {
+    results: Vec<CommandResult>,
+}
+
+impl Status {
+    /// The status of a command that was started. `allowed` marks a failure of
+    /// an `allow_failure` command, which is reported but doesn't fail the
+    /// hook.
+    pub fn of(result: &Result<Outcome>, allowed: bool) -> Status {
+        let (reason, exit_code) = match result {
+            Ok(Outcome::Exited(status)) if status.success() => return Status::Passed,
+            Ok(Outcome::Exited(status)) => match status.code() {
+                Some(code) => (format!("exit code {}", code), Some(code)),
+                None => ("terminated by a signal".to_string(), None),
+            },
+            Ok(Outcome::TimedOut(timeout)) => (format!("timed out after {}", timeout), None),
+            Err(e) => (format!("could not run: {}", e), None),
+        };
+        Status::Failed {
+            reason,
+            exit_code,
+            allowed,
+        }
+    }
+}
+
+impl RunReport {
+    /// Records the status of a command that ran, reporting a failure as soon
+    /// as it happens.
+    pub fn record(&mut self, id: &str, status: Status, elapsed: Duration) {
+        if let Status::Failed {
+            reason, allowed, ..
+        } = &status
+        {
+            let suffix = if *allowed { ", failure allowed" } else { "" };
+            eprintln!(
+                "{}",
+                format!("✗ {} failed ({}{})", id, reason, suffix).red()
+            );
+        }
+
+        self.results.push(CommandResult {
+            id: id.to_string(),
+            status,
+            elapsed: Some(elapsed),
+        });
+    }
+
+    pub fn skip(&mut self, id: &str, reason: impl Into<String>) {
+        self.results.push(CommandResult {
+            id: id.to_string(),
+            status: Status::Skipped(reason.into()),
+            elapsed: None,
+        });
+    }
+
+    /// The recorded status of a command, if it has been recorded yet.
+    pub fn status(&self, id: &str) -> Option<&Status> {
+        self.results
+            .iter()
+            .find(|result| result.id == id)
+            .map(|result| &result.status)
+    }
+
+    /// Failures of commands that are not allowed to fail.
+    fn required_failures(&self) -> Vec<&CommandResult> {
+        self.results
+            .iter()
+            .filter(|result| matches!(result.status, Status::Failed { allowed: false, .. }))
+            .collect()
+    }
+
+    pub fn has_required_failure(&self) -> bool {
+        !self.required_failures().is_empty()
+    }
+
+    pub fn print_summary(&self) {
+        if self.results.is_empty() {
+            return;
+        }
+
+        let width = self
+            .results
+            .iter()
+            .map(|result| result.id.len())
+            .max()
+            .unwrap_or(0);
+
+        println!();
+        println!("{}", "Summary:".bold());
+        for result in &self.results {
+            let elapsed = result
+                .elapsed
+                .map(|elapsed| format!("{:.2}s", elapsed.as_secs_f64()))
+                .unwrap_or_default();
+            let line = match &result.status {
+                Status::Passed => format!(
+                    "  ✓ {:width$}  {:8}  {:20}  {}",
+                    result.id, "passed", "exit code 0", elapsed
+                )
+                .green(),
+                Status::Failed {
+                    reason,
+                    allowed: true,
+                    ..
+                } => format!(
+                    "  ! {:width$}  {:8}  {:20}  {}",
+                    result.id, "allowed", reason, elapsed
+                )
+                .yellow(),
+                Status::Failed { reason, .. } => format!(
+                    "  ✗ {:width$}  {:8}  {:20}  {}",
+                    result.id, "failed", reason, elapsed
+                )
+                .red(),
+                Status::Skipped(reason) => {
+                    format!("  - {:width$}  {:8}  {}", result.id, "skipped", reason).dimmed()
+                }
+            };
+            println!("{}", line);
+        }
+    }
+
+    /// Turns the report into the hook's result: an error naming the failed
+    /// command(s) if any required command failed.
+    pub fn into_result(self) -> Result<()> {
+        let failures = self.required_failures();
+        match failures.as_slice() {
+            [] => Ok(()),
+            [failure] => {
+                let reason = match &failure.status {
+                    Status::Failed { reason, .. } => reason.clone(),
+                    _ => unreachable!(),
+                };
+                Err(HookmanError::CommandFailed(failure.id.clone(), reason).into())
+            }
+            failures => {
+                let ids: Vec<&str> = failures.iter().map(|f| f.id.as_str()).collect();
+                Err(HookmanError::CommandsFailed(ids.join(", ")).into())
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+
+    fn exited(code: i32, allowed: bool) -> Status {
+        Status::of(
+            &Ok(Outcome::Exited(ExitStatus::from_raw(code << 8))),
+            allowed,
+        )
+    }
+
+    #[test]
+    fn test_record_passed_and_failed() {
+        let mut report = RunReport::default();
+        report.record("ok", exited(0, false), Duration::ZERO);
+        report.record("bad", exited(2, false), Duration::ZERO);
+
+        assert_eq!(report.results[0].status, Status::Passed);
+        assert_eq!(
+            report.results[1].status,
+            Status::Failed {
+                reason: "exit code 2".to_string(),
+                exit_code: Some(2),
+                allowed: false
+            }
+        );
+        assert!(report.has_required_failure());
+    }
+
+    #[test]
+    fn test_status_of_timeout_and_spawn_error() {
+        let timeout = "2s".parse().unwrap();
+
+        assert_eq!(
+            Status::of(&Ok(Outcome::TimedOut(timeout)), false),
+            Status::Failed {
+                reason: "timed out after 2s".to_string(),
+                exit_code: None,
+                allowed: false
+            }
+        );
+        assert!(matches!(
+            Status::of(&Err(anyhow::anyhow!("no such file")), true),
+            Status::Failed { allowed: true, .. }
+        ));
+    }
+
+    #[test]
+    fn test_allowed_failure_does_not_fail_hook() {
+        let mut report = RunReport::default();
+        report.record("flaky", exited(1, true), Duration::ZERO);
+        report.skip("later", "not needed");
+
+        assert!(!report.has_required_failure());
+        assert!(report.into_result().is_ok());
+    }
+
+    #[test]
+    fn test_into_result_single_failure() {
+        let mut report = RunReport::default();
+        report.record("lint", exited(1, false), Duration::ZERO);
+
+        let err = report.into_result().unwrap_err();
+
+        assert_eq!(err.to_string(), "Command 'lint' failed (exit code 1)");
+    }
+
+    #[test]
+    fn test_into_result_multiple_failures() {
+        let mut report = RunReport::default();
+        report.record("lint", exited(1, false), Duration::ZERO);
+        report.record("fmt", exited(1, false), Duration::ZERO);
+
+        let err = report.into_result().unwrap_err();
+
+        assert_eq!(err.to_string(), "Commands failed: lint, fmt");
+    }
+}
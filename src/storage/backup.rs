@@ -0,0 +1,253 @@
+use crate::error::HookmanError;
+use crate::models::HookType;
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A saved copy of a hook script that hookman replaced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backup {
+    pub hook_type: HookType,
+    pub id: String,
+    pub path: PathBuf,
+}
+
+/// Timestamped hook backups, kept in a hookman-owned directory inside the
+/// Git directory so they are never committed or overwritten by `apply`.
+///
+/// Layout: `<dir>/<hook-type>/<id>`, where the id is a UTC timestamp such as
+/// `20261018T143015Z`, suffixed with `-N` when several backups of the same
+/// hook are taken within one second.
+pub struct BackupStore {
+    dir: PathBuf,
+}
+
+impl BackupStore {
+    pub fn new(dir: PathBuf) -> Self {
+        BackupStore { dir }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Copies `source` into the store. Returns `None` without writing when
+    /// the content is identical to the latest backup of the same hook.
+    pub fn save(&self, hook_type: HookType, source: &Path) -> Result<Option<Backup>> {
+        let contents = fs::read(source)?;
+
+        if let Some(latest) = self.latest(hook_type)? {
+            if fs::read(&latest.path)? == contents {
+                return Ok(None);
+            }
+        }
+
+        let hook_dir = self.dir.join(hook_type.as_str());
+        fs::create_dir_all(&hook_dir)?;
+
+        let timestamp = format_timestamp(SystemTime::now());
+        let mut id = timestamp.clone();
+        let mut suffix = 1;
+        while hook_dir.join(&id).exists() {
+            id = format!("{}-{}", timestamp, suffix);
+            suffix += 1;
+        }
+
+        let path = hook_dir.join(&id);
+        fs::copy(source, &path)?;
+
+        Ok(Some(Backup {
+            hook_type,
+            id,
+            path,
+        }))
+    }
+
+    /// Copies a backup back to `destination`, keeping its permissions.
+    pub fn restore(&self, backup: &Backup, destination: &Path) -> Result<()> {
+        fs::copy(&backup.path, destination)?;
+        Ok(())
+    }
+
+    /// Lists backups oldest first, for one hook type or for all of them.
+    pub fn list(&self, hook_type: Option<HookType>) -> Result<Vec<Backup>> {
+        let hook_types = match hook_type {
+            Some(hook_type) => vec![hook_type],
+            None => HookType::all(),
+        };
+
+        let mut backups = Vec::new();
+        for hook_type in hook_types {
+            let hook_dir = self.dir.join(hook_type.as_str());
+            if !hook_dir.is_dir() {
+                continue;
+            }
+
+            let mut hook_backups = Vec::new();
+            for entry in fs::read_dir(&hook_dir)? {
+                let path = entry?.path();
+                if let Some(id) = path.file_name().and_then(|s| s.to_str()) {
+                    if path.is_file() {
+                        hook_backups.push(Backup {
+                            hook_type,
+                            id: id.to_string(),
+                            path: path.clone(),
+                        });
+                    }
+                }
+            }
+            hook_backups.sort_by_key(|backup| sort_key(&backup.id));
+            backups.extend(hook_backups);
+        }
+
+        Ok(backups)
+    }
+
+    pub fn latest(&self, hook_type: HookType) -> Result<Option<Backup>> {
+        Ok(self.list(Some(hook_type))?.pop())
+    }
+
+    /// Finds a backup by id, or the latest one when no id is given.
+    pub fn find(&self, hook_type: HookType, id: Option<&str>) -> Result<Backup> {
+        let backup = match id {
+            Some(id) => self
+                .list(Some(hook_type))?
+                .into_iter()
+                .find(|backup| backup.id == id),
+            None => self.latest(hook_type)?,
+        };
+
+        backup.ok_or_else(|| {
+            HookmanError::BackupNotFound(hook_type.to_string(), id.unwrap_or("latest").to_string())
+                .into()
+        })
+    }
+}
+
+fn sort_key(id: &str) -> (String, u32) {
+    match id.split_once('-') {
+        Some((timestamp, suffix)) => (timestamp.to_string(), suffix.parse().unwrap_or(0)),
+        None => (id.to_string(), 0),
+    }
+}
+
+/// Formats a time as a compact UTC timestamp, e.g. `20261018T143015Z`.
+fn format_timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let days = (secs / 86_400) as i64;
+    let seconds_of_day = secs % 86_400;
+
+    // Civil-from-days conversion (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3_600,
+        (seconds_of_day / 60) % 60,
+        seconds_of_day % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(UNIX_EPOCH), "19700101T000000Z");
+        assert_eq!(
+            format_timestamp(UNIX_EPOCH + Duration::from_secs(951_782_400)),
+            "20000229T000000Z"
+        );
+        assert_eq!(
+            format_timestamp(UNIX_EPOCH + Duration::from_secs(1_792_333_815)),
+            "20261018T143015Z"
+        );
+    }
+
+    #[test]
+    fn test_save_and_list() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = BackupStore::new(temp_dir.path().join("backups"));
+        let hook = temp_dir.path().join("pre-commit");
+
+        fs::write(&hook, "first").unwrap();
+        let first = store.save(HookType::PreCommit, &hook).unwrap().unwrap();
+        fs::write(&hook, "second").unwrap();
+        let second = store.save(HookType::PreCommit, &hook).unwrap().unwrap();
+
+        let backups = store.list(Some(HookType::PreCommit)).unwrap();
+        assert_eq!(backups, vec![first.clone(), second.clone()]);
+        assert_eq!(fs::read_to_string(&first.path).unwrap(), "first");
+        assert_eq!(
+            store.latest(HookType::PreCommit).unwrap(),
+            Some(second.clone())
+        );
+        assert_eq!(
+            store.find(HookType::PreCommit, Some(&first.id)).unwrap(),
+            first
+        );
+        assert_eq!(store.find(HookType::PreCommit, None).unwrap(), second);
+    }
+
+    #[test]
+    fn test_save_skips_duplicate_of_latest() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = BackupStore::new(temp_dir.path().join("backups"));
+        let hook = temp_dir.path().join("pre-push");
+        fs::write(&hook, "same").unwrap();
+
+        assert!(store.save(HookType::PrePush, &hook).unwrap().is_some());
+        assert!(store.save(HookType::PrePush, &hook).unwrap().is_none());
+        assert_eq!(store.list(None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_find_missing_backup() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = BackupStore::new(temp_dir.path().join("backups"));
+
+        let err = store.find(HookType::PreCommit, None).unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<HookmanError>(),
+            Some(HookmanError::BackupNotFound(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_sort_key_orders_suffixes_numerically() {
+        let mut ids = vec![
+            "20261018T143015Z-10",
+            "20261018T143015Z-2",
+            "20261018T143015Z",
+        ];
+        ids.sort_by_key(|id| sort_key(id));
+
+        assert_eq!(
+            ids,
+            vec![
+                "20261018T143015Z",
+                "20261018T143015Z-2",
+                "20261018T143015Z-10"
+            ]
+        );
+    }
+}
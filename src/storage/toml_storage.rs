@@ -1,24 +1,34 @@
 use crate::error::HookmanError;
 use crate::models::{Config, Hook, HookType};
-use crate::storage::{get_hookman_dir, get_hooks_dir, Storage, CONFIG_FILE};
+use crate::storage::{Storage, CONFIG_FILE, HOOKS_DIR};
 use anyhow::Result;
 use std::fs;
+use std::path::{Path, PathBuf};
 
-pub struct TomlStorage;
+pub struct TomlStorage {
+    hookman_dir: PathBuf,
+}
 
 impl TomlStorage {
-    pub fn new() -> Self {
-        TomlStorage
+    pub fn new(hookman_dir: PathBuf) -> Self {
+        TomlStorage { hookman_dir }
+    }
+
+    pub fn hookman_dir(&self) -> &Path {
+        &self.hookman_dir
+    }
+
+    fn hooks_dir(&self) -> PathBuf {
+        self.hookman_dir.join(HOOKS_DIR)
     }
 }
 
 impl Storage for TomlStorage {
     fn init(&self) -> Result<()> {
-        let hookman_dir = get_hookman_dir()?;
-        let hooks_dir = get_hooks_dir()?;
+        let hooks_dir = self.hooks_dir();
 
         // Create the .hookman directory first
-        fs::create_dir_all(&hookman_dir)?;
+        fs::create_dir_all(&self.hookman_dir)?;
         // Then create the hooks subdirectory
         fs::create_dir_all(&hooks_dir)?;
 
@@ -29,11 +39,11 @@ impl Storage for TomlStorage {
     }
 
     fn is_initialized(&self) -> bool {
-        get_hookman_dir().map(|dir| dir.exists()).unwrap_or(false)
+        self.hookman_dir.exists()
     }
 
     fn load_hook(&self, hook_type: HookType) -> Result<Hook> {
-        let hooks_dir = get_hooks_dir()?;
+        let hooks_dir = self.hooks_dir();
         let hook_file = hooks_dir.join(format!("{}.toml", hook_type.as_str()));
 
         if !hook_file.exists() {
@@ -50,7 +60,7 @@ impl Storage for TomlStorage {
     }
 
     fn save_hook(&self, hook: &Hook) -> Result<()> {
-        let hooks_dir = get_hooks_dir()?;
+        let hooks_dir = self.hooks_dir();
         let hook_file = hooks_dir.join(format!("{}.toml", hook.hook_type.as_str()));
 
         let contents = toml::to_string_pretty(&hook)?;
@@ -61,7 +71,7 @@ impl Storage for TomlStorage {
     }
 
     fn list_hooks(&self) -> Result<Vec<HookType>> {
-        let hooks_dir = get_hooks_dir()?;
+        let hooks_dir = self.hooks_dir();
         let mut hooks = Vec::new();
 
         if !hooks_dir.exists() {
@@ -85,7 +95,7 @@ impl Storage for TomlStorage {
     }
 
     fn load_config(&self) -> Result<Config> {
-        let config_file = get_hookman_dir()?.join(CONFIG_FILE);
+        let config_file = self.hookman_dir.join(CONFIG_FILE);
 
         if !config_file.exists() {
             return Ok(Config::default());
@@ -98,7 +108,7 @@ impl Storage for TomlStorage {
     }
 
     fn save_config(&self, config: &Config) -> Result<()> {
-        let config_file = get_hookman_dir()?.join(CONFIG_FILE);
+        let config_file = self.hookman_dir.join(CONFIG_FILE);
 
         let contents = toml::to_string_pretty(&config)?;
         fs::write(&config_file, contents)
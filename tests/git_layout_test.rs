@@ -0,0 +1,211 @@
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+use tempfile::TempDir;
+
+// Runs hookman isolated from the developer's own Git configuration.
+fn hookman_command(dir: &Path) -> std::process::Command {
+    let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_hookman"));
+    command
+        .current_dir(dir)
+        .env_remove("GIT_DIR")
+        .env_remove("GIT_COMMON_DIR")
+        .env_remove("GIT_WORK_TREE")
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("GIT_CONFIG_GLOBAL", "/dev/null");
+    command
+}
+
+fn hookman(dir: &Path, args: &[&str]) -> Output {
+    hookman_command(dir)
+        .args(args)
+        .output()
+        .expect("Failed to execute hookman")
+}
+
+fn init_and_apply(dir: &Path) {
+    let output = hookman(dir, &["init"]);
+    assert!(output.status.success(), "{:?}", output);
+
+    let output = hookman(dir, &["add", "pre-commit", "echo test", "--id", "test"]);
+    assert!(output.status.success(), "{:?}", output);
+
+    let output = hookman(dir, &["apply"]);
+    assert!(output.status.success(), "{:?}", output);
+}
+
+// Mirrors the layout `git worktree add` creates: the main repository keeps a
+// per-worktree git dir under .git/worktrees/<name>, and the worktree's .git is
+// a file pointing back at it.
+#[test]
+fn test_apply_in_linked_worktree() {
+    let temp_dir = TempDir::new().unwrap();
+    let main = temp_dir.path().join("main");
+    fs::create_dir_all(main.join(".git/hooks")).unwrap();
+
+    let worktree_git_dir = main.join(".git/worktrees/feature");
+    fs::create_dir_all(&worktree_git_dir).unwrap();
+    fs::write(worktree_git_dir.join("commondir"), "../..\n").unwrap();
+
+    let worktree = temp_dir.path().join("feature");
+    fs::create_dir_all(worktree.join("src")).unwrap();
+    fs::write(
+        worktree.join(".git"),
+        format!("gitdir: {}\n", worktree_git_dir.display()),
+    )
+    .unwrap();
+
+    init_and_apply(&worktree.join("src"));
+
+    assert!(worktree.join(".hookman/hooks/pre-commit.toml").exists());
+    assert!(main.join(".git/hooks/pre-commit").exists());
+    assert!(!worktree_git_dir.join("hooks/pre-commit").exists());
+}
+
+// Mirrors a submodule checkout: the submodule's .git is a file with a
+// relative gitdir pointing into the superproject's .git/modules.
+#[test]
+fn test_apply_in_submodule() {
+    let temp_dir = TempDir::new().unwrap();
+    let superproject = temp_dir.path();
+    fs::create_dir_all(superproject.join(".git/hooks")).unwrap();
+
+    let module_git_dir = superproject.join(".git/modules/vendor/lib");
+    fs::create_dir_all(module_git_dir.join("hooks")).unwrap();
+
+    let submodule = superproject.join("vendor/lib");
+    fs::create_dir_all(&submodule).unwrap();
+    fs::write(
+        submodule.join(".git"),
+        "gitdir: ../../.git/modules/vendor/lib\n",
+    )
+    .unwrap();
+
+    init_and_apply(&submodule);
+
+    assert!(submodule.join(".hookman").exists());
+    assert!(module_git_dir.join("hooks/pre-commit").exists());
+    assert!(!superproject.join(".git/hooks/pre-commit").exists());
+    assert!(!superproject.join(".hookman").exists());
+}
+
+#[test]
+fn test_git_dir_and_common_dir_env() {
+    let temp_dir = TempDir::new().unwrap();
+    let work_tree = temp_dir.path().join("work");
+    fs::create_dir_all(&work_tree).unwrap();
+    let git_dir = temp_dir.path().join("repo.git");
+    fs::create_dir_all(&git_dir).unwrap();
+    let common_dir = temp_dir.path().join("common.git");
+    fs::create_dir_all(&common_dir).unwrap();
+
+    let run = |args: &[&str]| {
+        hookman_command(&work_tree)
+            .env("GIT_DIR", &git_dir)
+            .env("GIT_COMMON_DIR", &common_dir)
+            .args(args)
+            .output()
+            .expect("Failed to execute hookman")
+    };
+
+    assert!(run(&["init"]).status.success());
+    assert!(run(&["add", "pre-commit", "echo test", "--id", "test"])
+        .status
+        .success());
+    assert!(run(&["apply"]).status.success());
+
+    assert!(work_tree.join(".hookman").exists());
+    assert!(common_dir.join("hooks/pre-commit").exists());
+    assert!(!git_dir.join("hooks/pre-commit").exists());
+}
+
+#[test]
+fn test_broken_gitdir_file() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join(".git"), "gitdir: does/not/exist\n").unwrap();
+
+    let output = hookman(temp_dir.path(), &["init"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Invalid git directory"));
+}
+
+#[test]
+fn test_apply_honors_local_hooks_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path();
+    fs::create_dir_all(repo.join(".git/hooks")).unwrap();
+    fs::write(
+        repo.join(".git/config"),
+        "[core]\n\thooksPath = .githooks\n",
+    )
+    .unwrap();
+
+    init_and_apply(repo);
+
+    assert!(repo.join(".githooks/pre-commit").exists());
+    assert!(!repo.join(".git/hooks/pre-commit").exists());
+
+    let output = hookman(repo, &["status"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("from core.hooksPath"));
+    assert!(stdout.contains("applied"));
+    assert!(!stdout.contains("Warning"));
+}
+
+#[test]
+fn test_apply_honors_global_hooks_path_via_include() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path().join("repo");
+    fs::create_dir_all(repo.join(".git/hooks")).unwrap();
+    let shared_hooks = temp_dir.path().join("shared-hooks");
+    let included = temp_dir.path().join("hooks.gitconfig");
+    fs::write(
+        &included,
+        format!("[core]\n\thooksPath = {}\n", shared_hooks.display()),
+    )
+    .unwrap();
+    let global = temp_dir.path().join("gitconfig");
+    fs::write(&global, "[include]\n\tpath = hooks.gitconfig\n").unwrap();
+
+    let run = |args: &[&str]| {
+        hookman_command(&repo)
+            .env("GIT_CONFIG_GLOBAL", &global)
+            .args(args)
+            .output()
+            .expect("Failed to execute hookman")
+    };
+
+    assert!(run(&["init"]).status.success());
+    assert!(run(&["add", "pre-commit", "echo test", "--id", "test"])
+        .status
+        .success());
+    assert!(run(&["apply"]).status.success());
+
+    assert!(shared_hooks.join("pre-commit").exists());
+    assert!(!repo.join(".git/hooks/pre-commit").exists());
+}
+
+#[test]
+fn test_status_warns_when_hooks_path_changed_after_apply() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path();
+    fs::create_dir_all(repo.join(".git/hooks")).unwrap();
+
+    init_and_apply(repo);
+    assert!(repo.join(".git/hooks/pre-commit").exists());
+
+    fs::write(
+        repo.join(".git/config"),
+        "[core]\n\thooksPath = .githooks\n",
+    )
+    .unwrap();
+
+    let output = hookman(repo, &["status"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Warning"));
+    assert!(stdout.contains("core.hooksPath points Git to"));
+    assert!(stdout.contains("not applied"));
+}
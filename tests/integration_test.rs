@@ -35,7 +35,7 @@ fn test_hookman_workflow() {
     // Test add command
     let output = std::process::Command::new(env!("CARGO_BIN_EXE_hookman"))
         .current_dir(repo_path)
-        .args(&[
+        .args([
             "add",
             "pre-commit",
             "echo 'test'",
@@ -84,7 +84,7 @@ fn test_hookman_workflow() {
     assert!(git_hook.exists());
     let content = fs::read_to_string(&git_hook).unwrap();
     assert!(content.contains("Generated by hookman"));
-    assert!(content.contains("echo 'test'"));
+    assert!(content.contains("run pre-commit"));
 
     // Verify the hook is executable
     #[cfg(unix)]
@@ -98,7 +98,7 @@ fn test_hookman_workflow() {
     // Test remove command
     let output = std::process::Command::new(env!("CARGO_BIN_EXE_hookman"))
         .current_dir(repo_path)
-        .args(&["remove", "pre-commit", "test"])
+        .args(["remove", "pre-commit", "test"])
         .output()
         .expect("Failed to execute remove command");
 
@@ -131,7 +131,7 @@ fn test_hookman_not_initialized() {
 
     let output = std::process::Command::new(env!("CARGO_BIN_EXE_hookman"))
         .current_dir(temp_dir.path())
-        .args(&["add", "pre-commit", "echo test", "--id", "test"])
+        .args(["add", "pre-commit", "echo test", "--id", "test"])
         .output()
         .expect("Failed to execute add command");
 
@@ -139,3 +139,238 @@ fn test_hookman_not_initialized() {
     let stderr = String::from_utf8_lossy(&output.stderr);
     assert!(stderr.contains("Hookman not initialized"));
 }
+
+#[test]
+fn test_commands_from_subdirectory() {
+    let temp_dir = setup_test_repo();
+    let repo_path = temp_dir.path();
+    let nested = repo_path.join("src/nested");
+    fs::create_dir_all(&nested).unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_hookman"))
+        .current_dir(&nested)
+        .arg("init")
+        .output()
+        .expect("Failed to execute init command");
+
+    assert!(output.status.success());
+    assert!(repo_path.join(".hookman/config.toml").exists());
+    assert!(!nested.join(".hookman").exists());
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_hookman"))
+        .current_dir(&nested)
+        .args(["add", "pre-commit", "echo test", "--id", "test"])
+        .output()
+        .expect("Failed to execute add command");
+
+    assert!(output.status.success());
+    assert!(repo_path.join(".hookman/hooks/pre-commit.toml").exists());
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_hookman"))
+        .current_dir(&nested)
+        .arg("apply")
+        .output()
+        .expect("Failed to execute apply command");
+
+    assert!(output.status.success());
+    assert!(repo_path.join(".git/hooks/pre-commit").exists());
+}
+
+#[test]
+fn test_repo_option() {
+    let temp_dir = setup_test_repo();
+    let repo_path = temp_dir.path();
+    let outside = TempDir::new().unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_hookman"))
+        .current_dir(outside.path())
+        .arg("-C")
+        .arg(repo_path)
+        .arg("init")
+        .output()
+        .expect("Failed to execute init command");
+
+    assert!(output.status.success());
+    assert!(repo_path.join(".hookman").exists());
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_hookman"))
+        .current_dir(outside.path())
+        .arg("status")
+        .arg("--repo")
+        .arg(repo_path)
+        .output()
+        .expect("Failed to execute status command");
+
+    assert!(output.status.success());
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_hookman"))
+        .current_dir(outside.path())
+        .args(["--repo", "does-not-exist", "status"])
+        .output()
+        .expect("Failed to execute status command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("does not exist"));
+}
+
+#[test]
+fn test_status_detects_drift() {
+    let temp_dir = setup_test_repo();
+    let repo_path = temp_dir.path();
+    let run = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_hookman"))
+            .current_dir(repo_path)
+            .args(args)
+            .output()
+            .expect("Failed to execute hookman")
+    };
+
+    assert!(run(&["init"]).status.success());
+    assert!(run(&["add", "pre-commit", "echo one", "--id", "one"])
+        .status
+        .success());
+
+    let output = run(&["status", "--check"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("not applied"));
+
+    assert!(run(&["apply"]).status.success());
+    let output = run(&["status", "--check"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("1 commands, applied"));
+
+    // The shim loads commands when it runs, so editing them needs no apply
+    assert!(run(&["add", "pre-commit", "echo two", "--id", "two"])
+        .status
+        .success());
+    let output = run(&["status", "--check"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("2 commands, applied"));
+
+    // A script from a hookman version without hashes
+    let git_hook = repo_path.join(".git/hooks/pre-commit");
+    fs::write(&git_hook, "#!/bin/sh\n# Generated by hookman\necho one\n").unwrap();
+    let output = run(&["status", "--check"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("outdated"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("out of sync"));
+
+    // Plain status reports drift without failing
+    assert!(run(&["status"]).status.success());
+
+    assert!(run(&["apply"]).status.success());
+    let script = fs::read_to_string(&git_hook).unwrap();
+    fs::write(&git_hook, script.replace("exec ", "echo edited\nexec ")).unwrap();
+    let output = run(&["status", "--check"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("modified by hand"));
+
+    fs::write(&git_hook, "#!/bin/sh\nexit 0\n").unwrap();
+    let output = run(&["status", "--check"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("external hook exists"));
+}
+
+#[test]
+fn test_uninstall_restores_backups() {
+    let temp_dir = setup_test_repo();
+    let repo_path = temp_dir.path();
+    let hooks_dir = repo_path.join(".git/hooks");
+    let run = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_hookman"))
+            .current_dir(repo_path)
+            .args(args)
+            .output()
+            .expect("Failed to execute hookman")
+    };
+
+    fs::write(hooks_dir.join("pre-commit"), "#!/bin/sh\necho original\n").unwrap();
+    fs::write(hooks_dir.join("post-merge"), "#!/bin/sh\necho external\n").unwrap();
+
+    assert!(run(&["init"]).status.success());
+    assert!(run(&["add", "pre-commit", "echo one", "--id", "one"])
+        .status
+        .success());
+    assert!(run(&["add", "pre-push", "echo two", "--id", "two"])
+        .status
+        .success());
+    assert!(run(&["apply"]).status.success());
+
+    let output = run(&["uninstall", "--dry-run"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Would remove"));
+    assert!(stdout.contains("Would restore"));
+    assert!(fs::read_to_string(hooks_dir.join("pre-commit"))
+        .unwrap()
+        .contains("Generated by hookman"));
+
+    let output = run(&["uninstall", "pre-push"]);
+    assert!(output.status.success());
+    assert!(!hooks_dir.join("pre-push").exists());
+    assert!(hooks_dir.join("pre-commit").exists());
+
+    let output = run(&["uninstall"]);
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(hooks_dir.join("pre-commit")).unwrap(),
+        "#!/bin/sh\necho original\n"
+    );
+    assert!(!hooks_dir.join("pre-commit.backup").exists());
+    assert!(hooks_dir.join("post-merge").exists());
+
+    let output = run(&["uninstall", "post-merge"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("not managed by hookman"));
+    assert!(hooks_dir.join("post-merge").exists());
+}
+
+#[test]
+fn test_backups_survive_repeated_apply() {
+    let temp_dir = setup_test_repo();
+    let repo_path = temp_dir.path();
+    let hooks_dir = repo_path.join(".git/hooks");
+    let run = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_hookman"))
+            .current_dir(repo_path)
+            .args(args)
+            .output()
+            .expect("Failed to execute hookman")
+    };
+    let original = "#!/bin/sh\necho original\n";
+    fs::write(hooks_dir.join("pre-commit"), original).unwrap();
+
+    assert!(run(&["init"]).status.success());
+    assert!(run(&["add", "pre-commit", "echo one", "--id", "one"])
+        .status
+        .success());
+    assert!(run(&["apply"]).status.success());
+    assert!(run(&["apply"]).status.success());
+
+    let backup_dir = repo_path.join(".git/hookman/backups/pre-commit");
+    let backups: Vec<_> = fs::read_dir(&backup_dir).unwrap().collect();
+    assert_eq!(backups.len(), 1);
+    let backup_path = backups[0].as_ref().unwrap().path();
+    assert_eq!(fs::read_to_string(&backup_path).unwrap(), original);
+    assert!(!hooks_dir.join("pre-commit.backup").exists());
+
+    let output = run(&["backups", "list"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let backup_id = backup_path.file_name().unwrap().to_str().unwrap();
+    assert!(stdout.contains("pre-commit"));
+    assert!(stdout.contains(backup_id));
+    assert!(stdout.contains("(latest)"));
+
+    let output = run(&["backups", "restore", "pre-commit", backup_id]);
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(hooks_dir.join("pre-commit")).unwrap(),
+        original
+    );
+
+    let output = run(&["backups", "restore", "pre-commit", "nope"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No backup 'nope'"));
+}
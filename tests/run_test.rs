@@ -0,0 +1,1712 @@
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+use tempfile::TempDir;
+
+fn hookman_command(dir: &Path) -> std::process::Command {
+    let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_hookman"));
+    command
+        .current_dir(dir)
+        .env_remove("GIT_DIR")
+        .env_remove("GIT_COMMON_DIR")
+        .env_remove("GIT_WORK_TREE")
+        .env_remove("HOOKMAN")
+        .env_remove("HOOKMAN_SKIP")
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("GIT_CONFIG_GLOBAL", "/dev/null");
+    command
+}
+
+fn hookman(dir: &Path, args: &[&str]) -> Output {
+    hookman_command(dir)
+        .args(args)
+        .output()
+        .expect("Failed to execute hookman")
+}
+
+fn git(dir: &Path, args: &[&str]) -> Output {
+    std::process::Command::new("git")
+        .current_dir(dir)
+        .env_remove("GIT_DIR")
+        .env_remove("GIT_COMMON_DIR")
+        .env_remove("GIT_WORK_TREE")
+        .env_remove("HOOKMAN")
+        .env_remove("HOOKMAN_SKIP")
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("GIT_CONFIG_GLOBAL", "/dev/null")
+        .args([
+            "-c",
+            "user.name=Hookman",
+            "-c",
+            "user.email=hookman@example.com",
+        ])
+        .args(args)
+        .output()
+        .expect("Failed to execute git")
+}
+
+// Creates a real Git repository with hookman initialized.
+fn setup_git_repo() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    assert!(git(temp_dir.path(), &["init", "-q"]).status.success());
+    assert!(hookman(temp_dir.path(), &["init"]).status.success());
+    temp_dir
+}
+
+fn add(dir: &Path, hook: &str, command: &str, id: &str) {
+    let output = hookman(dir, &["add", hook, command, "--id", id]);
+    assert!(output.status.success(), "{:?}", output);
+}
+
+fn commit(dir: &Path, file: &str) -> Output {
+    fs::write(dir.join(file), file).unwrap();
+    assert!(git(dir, &["add", file]).status.success());
+    git(dir, &["commit", "-q", "-m", "test commit"])
+}
+
+#[test]
+fn test_run_executes_commands() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    add(repo, "pre-commit", "echo first", "first");
+    add(repo, "pre-commit", "echo second", "second");
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("→ first"));
+    assert!(stdout.find("first\n").unwrap() < stdout.find("second\n").unwrap());
+    assert!(stdout.contains("All pre-commit hooks passed!"));
+}
+
+#[test]
+fn test_run_stops_at_first_failure() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    add(repo, "pre-commit", "exit 3", "fail");
+    add(repo, "pre-commit", "echo should-not-run", "after");
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stdout.contains("should-not-run"));
+    assert!(stderr.contains("Command 'fail' failed (exit code 3)"));
+}
+
+#[test]
+fn test_run_passes_arguments() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    add(
+        repo,
+        "commit-msg",
+        r#"test "$1" = msg.txt && test "$HOOKMAN_COMMIT_MSG_FILE" = msg.txt"#,
+        "args",
+    );
+
+    let output = hookman(repo, &["run", "commit-msg", "--", "msg.txt"]);
+
+    assert!(output.status.success(), "{:?}", output);
+}
+
+#[test]
+fn test_run_runs_from_repository_root() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::create_dir_all(repo.join("nested")).unwrap();
+    add(repo, "pre-commit", "test -d .hookman", "root");
+
+    let output = hookman(&repo.join("nested"), &["run", "pre-commit"]);
+
+    assert!(output.status.success(), "{:?}", output);
+}
+
+#[test]
+fn test_applied_hook_picks_up_config_changes() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    add(repo, "pre-commit", "touch first-ran", "first");
+    assert!(hookman(repo, &["apply"]).status.success());
+
+    assert!(commit(repo, "a.txt").status.success());
+    assert!(repo.join("first-ran").exists());
+
+    // No re-apply: the installed hook loads the configuration at run time.
+    add(repo, "pre-commit", "touch second-ran", "second");
+    assert!(commit(repo, "b.txt").status.success());
+    assert!(repo.join("second-ran").exists());
+
+    add(repo, "pre-commit", "exit 1", "block");
+    let output = commit(repo, "c.txt");
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_applied_hook_replays_stdin() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    add(repo, "pre-push", "cat > first.txt", "first");
+    add(repo, "pre-push", "cat > second.txt", "second");
+    assert!(hookman(repo, &["apply"]).status.success());
+    assert!(commit(repo, "a.txt").status.success());
+
+    let remote = TempDir::new().unwrap();
+    assert!(git(remote.path(), &["init", "-q", "--bare"])
+        .status
+        .success());
+    let output = git(
+        repo,
+        &[
+            "push",
+            "-q",
+            remote.path().to_str().unwrap(),
+            "HEAD:refs/heads/main",
+        ],
+    );
+    assert!(output.status.success(), "{:?}", output);
+
+    let first = fs::read_to_string(repo.join("first.txt")).unwrap();
+    let second = fs::read_to_string(repo.join("second.txt")).unwrap();
+    assert!(first.contains("refs/heads/main"));
+    assert_eq!(first, second);
+}
+
+#[cfg(unix)]
+fn install_hook(repo: &Path, hook: &str, script: &str) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = repo.join(".git/hooks").join(hook);
+    fs::write(&path, script).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_chain_runs_existing_hook_before_commands() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    install_hook(
+        repo,
+        "commit-msg",
+        "#!/bin/sh\necho \"original $(head -n1 \"$1\")\" >> order.log\n",
+    );
+    fs::write(
+        repo.join(".hookman/hooks/commit-msg.toml"),
+        r#"chain = "before"
+
+[[commands]]
+id = "mine"
+command = "echo hookman >> order.log"
+"#,
+    )
+    .unwrap();
+    assert!(hookman(repo, &["apply"]).status.success());
+
+    let output = commit(repo, "a.txt");
+    assert!(output.status.success(), "{:?}", output);
+
+    assert_eq!(
+        fs::read_to_string(repo.join("order.log")).unwrap(),
+        "original test commit\nhookman\n"
+    );
+
+    // Re-applying keeps the preserved original rather than chaining to the
+    // hookman script now in its place.
+    assert!(hookman(repo, &["apply"]).status.success());
+    let chained = repo.join(".git/hookman/chained/commit-msg");
+    assert!(!fs::read_to_string(chained)
+        .unwrap()
+        .contains("Generated by hookman"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_chain_runs_existing_hook_after_commands_with_stdin() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    install_hook(
+        repo,
+        "pre-push",
+        "#!/bin/sh\necho \"original $1\" >> order.log\ncat >> order.log\n",
+    );
+    fs::write(
+        repo.join(".hookman/hooks/pre-push.toml"),
+        r#"chain = "after"
+
+[[commands]]
+id = "mine"
+command = "echo hookman >> order.log"
+"#,
+    )
+    .unwrap();
+    assert!(hookman(repo, &["apply"]).status.success());
+
+    let output = hookman_command(repo)
+        .args(["run", "pre-push", "--", "origin", "url"])
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            use std::io::Write;
+            child
+                .stdin
+                .take()
+                .unwrap()
+                .write_all(b"refs/heads/main 1 refs/heads/main 2\n")?;
+            child.wait_with_output()
+        })
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+
+    assert_eq!(
+        fs::read_to_string(repo.join("order.log")).unwrap(),
+        "hookman\noriginal origin\nrefs/heads/main 1 refs/heads/main 2\n"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_chain_set_after_apply_uses_backup() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    install_hook(repo, "pre-commit", "#!/bin/sh\ntouch original-ran\n");
+    add(repo, "pre-commit", "true", "mine");
+    assert!(hookman(repo, &["apply"]).status.success());
+
+    // Without a preserved hook, chain warns rather than silently doing nothing.
+    let config = repo.join(".hookman/hooks/pre-commit.toml");
+    let toml = fs::read_to_string(&config).unwrap();
+    fs::write(&config, format!("chain = \"before\"\n{}", toml)).unwrap();
+    let output = hookman(repo, &["run", "pre-commit"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no pre-existing hook was preserved"));
+    assert!(!repo.join("original-ran").exists());
+    let output = hookman(repo, &["status", "--check"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("outdated (no hook to chain)"));
+
+    // The original was replaced by the first apply; the next one chains its
+    // backup.
+    assert!(hookman(repo, &["apply"]).status.success());
+    let output = hookman(repo, &["run", "pre-commit"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(repo.join("original-ran").exists());
+    let output = hookman(repo, &["status", "--check"]);
+    assert!(output.status.success(), "{:?}", output);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_chained_hook_failure_fails_hook() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    install_hook(repo, "pre-commit", "#!/bin/sh\nexit 1\n");
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"chain = "before"
+
+[[commands]]
+id = "mine"
+command = "touch should-not-run"
+"#,
+    )
+    .unwrap();
+    assert!(hookman(repo, &["apply"]).status.success());
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Command 'chained' failed"));
+    assert!(!repo.join("should-not-run").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_without_chain_existing_hook_is_replaced() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    install_hook(repo, "pre-commit", "#!/bin/sh\ntouch original-ran\n");
+    add(repo, "pre-commit", "true", "mine");
+    assert!(hookman(repo, &["apply"]).status.success());
+
+    assert!(commit(repo, "a.txt").status.success());
+
+    assert!(!repo.join("original-ran").exists());
+}
+
+#[test]
+fn test_run_enforces_command_timeout() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"[[commands]]
+id = "slow"
+command = "sleep 30"
+timeout = "300ms"
+
+[[commands]]
+id = "after"
+command = "touch should-not-run"
+"#,
+    )
+    .unwrap();
+
+    let started = std::time::Instant::now();
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(!output.status.success());
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Command 'slow' failed (timed out after 300ms)"));
+    assert!(!repo.join("should-not-run").exists());
+}
+
+#[test]
+fn test_run_uses_hook_level_timeout() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"timeout = "300ms"
+
+[[commands]]
+id = "fast"
+command = "true"
+timeout = "10s"
+
+[[commands]]
+id = "slow"
+command = "sleep 30"
+"#,
+    )
+    .unwrap();
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Command 'slow' failed (timed out after 300ms)"));
+}
+
+#[test]
+fn test_run_without_fail_fast_runs_every_command() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"fail_fast = false
+
+[[commands]]
+id = "lint"
+command = "exit 2"
+
+[[commands]]
+id = "format"
+command = "exit 1"
+
+[[commands]]
+id = "test"
+command = "touch tests-ran"
+"#,
+    )
+    .unwrap();
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(!output.status.success());
+    assert!(repo.join("tests-ran").exists());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stdout.contains("Summary:"));
+    assert!(stdout.contains("exit code 2"));
+    assert!(stdout.contains("passed"));
+    assert!(stderr.contains("Commands failed: lint, format"));
+}
+
+#[test]
+fn test_run_fail_fast_reports_skipped_commands() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    add(repo, "pre-commit", "exit 1", "fail");
+    add(repo, "pre-commit", "true", "after");
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("skipped   earlier command failed"));
+}
+
+#[test]
+fn test_allowed_failure_does_not_fail_hook() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"[[commands]]
+id = "flaky"
+command = "exit 1"
+allow_failure = true
+
+[[commands]]
+id = "after"
+command = "touch after-ran"
+"#,
+    )
+    .unwrap();
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(output.status.success(), "{:?}", output);
+    assert!(repo.join("after-ran").exists());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("allowed"));
+    assert!(stdout.contains("All pre-commit hooks passed!"));
+}
+
+#[test]
+fn test_run_parallel_runs_commands_concurrently() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    // Each command waits for the other's marker file, so they can only pass
+    // if both run at the same time.
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"parallel = true
+concurrency = 2
+timeout = "10s"
+
+[[commands]]
+id = "first"
+command = "echo first-output; touch first; while [ ! -e second ]; do sleep 0.05; done"
+
+[[commands]]
+id = "second"
+command = "touch second; while [ ! -e first ]; do sleep 0.05; done; echo second-output"
+"#,
+    )
+    .unwrap();
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let first = stdout.find("→ first").unwrap();
+    let second = stdout.find("→ second").unwrap();
+    assert!(first < stdout.find("first-output").unwrap());
+    assert!(stdout.find("first-output").unwrap() < second);
+    assert!(second < stdout.find("second-output").unwrap());
+}
+
+#[test]
+fn test_run_parallel_respects_concurrency_limit() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    let command = "mkdir running || exit 1; sleep 0.2; rmdir running";
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        format!(
+            "parallel = true\nconcurrency = 1\n\n\
+             [[commands]]\nid = \"a\"\ncommand = \"{command}\"\n\n\
+             [[commands]]\nid = \"b\"\ncommand = \"{command}\"\n"
+        ),
+    )
+    .unwrap();
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(output.status.success(), "{:?}", output);
+}
+
+#[test]
+fn test_run_parallel_failure_fails_hook() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"parallel = true
+concurrency = 1
+
+[[commands]]
+id = "fail"
+command = "echo broken; exit 3"
+
+[[commands]]
+id = "after"
+command = "touch should-not-run"
+"#,
+    )
+    .unwrap();
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(!output.status.success());
+    assert!(!repo.join("should-not-run").exists());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stdout.contains("broken"));
+    assert!(stderr.contains("Command 'fail' failed (exit code 3)"));
+}
+
+#[test]
+fn test_run_orders_commands_by_dependencies() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"fail_fast = false
+
+[[commands]]
+id = "test"
+command = "test -e built && touch tested"
+depends_on = ["build"]
+
+[[commands]]
+id = "build"
+command = "touch built"
+
+[[commands]]
+id = "lint"
+command = "exit 1"
+
+[[commands]]
+id = "docs"
+command = "touch docs-ran"
+depends_on = ["lint"]
+"#,
+    )
+    .unwrap();
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(!output.status.success());
+    assert!(repo.join("tested").exists());
+    assert!(!repo.join("docs-ran").exists());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("skipped   because lint failed"));
+}
+
+#[test]
+fn test_run_parallel_waits_for_dependencies() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"parallel = true
+concurrency = 4
+
+[[commands]]
+id = "test"
+command = "test -e built"
+depends_on = ["build"]
+
+[[commands]]
+id = "build"
+command = "sleep 0.2; touch built"
+"#,
+    )
+    .unwrap();
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.find("→ build").unwrap() < stdout.find("→ test").unwrap());
+}
+
+#[test]
+fn test_run_rejects_dependency_cycles() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"[[commands]]
+id = "a"
+command = "true"
+depends_on = ["b"]
+
+[[commands]]
+id = "b"
+command = "true"
+depends_on = ["a"]
+"#,
+    )
+    .unwrap();
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Dependency cycle in hook 'pre-commit': a -> b -> a"));
+}
+
+#[test]
+fn test_invalid_hook_can_be_listed_and_repaired() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"[[commands]]
+id = "ok"
+command = "true"
+
+[[commands]]
+id = "broken"
+command = "true"
+depends_on = ["missing"]
+"#,
+    )
+    .unwrap();
+
+    assert!(!hookman(repo, &["apply"]).status.success());
+    assert!(!repo.join(".git/hooks/pre-commit").exists());
+    assert!(!hookman(repo, &["disable", "pre-commit", "ok"])
+        .status
+        .success());
+
+    let output = hookman(repo, &["list", "pre-commit"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("broken"));
+
+    let output = hookman(repo, &["status", "--check"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains(
+        "invalid: Command 'broken' in hook 'pre-commit' depends on unknown command 'missing'"
+    ));
+
+    let output = hookman(repo, &["remove", "pre-commit", "broken"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(hookman(repo, &["apply"]).status.success());
+}
+
+#[test]
+fn test_remove_refuses_command_with_dependents() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"[[commands]]
+id = "build"
+command = "true"
+
+[[commands]]
+id = "test"
+command = "true"
+depends_on = ["build"]
+"#,
+    )
+    .unwrap();
+
+    let output = hookman(repo, &["remove", "pre-commit", "build"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Command 'build' can't be removed, it is required by: test"));
+}
+
+#[test]
+fn test_files_placeholder_needs_changed_files() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+
+    // Other hooks have no changed files to substitute.
+    let output = hookman(
+        repo,
+        &["add", "post-commit", "eslint {files}", "--id", "lint"],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("{files} is only available in pre-commit and pre-push hooks"));
+
+    // With nothing staged, the command is skipped rather than run on
+    // everything.
+    add(repo, "pre-commit", "echo {files} > files.txt", "list");
+    let output = hookman(repo, &["run", "pre-commit"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("no matching files"));
+    assert!(!repo.join("files.txt").exists());
+}
+
+#[test]
+fn test_commands_run_only_on_matching_staged_files() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"[[commands]]
+id = "rust"
+command = "echo {files} > rust-files.txt"
+files = ["*.rs"]
+exclude = ["generated_*.rs"]
+
+[[commands]]
+id = "all"
+command = 'printf "%s\n" "$HOOKMAN_FILES" > all-files.txt'
+"#,
+    )
+    .unwrap();
+    assert!(hookman(repo, &["apply"]).status.success());
+
+    assert!(commit(repo, "notes.md").status.success());
+    assert!(!repo.join("rust-files.txt").exists());
+    assert_eq!(
+        fs::read_to_string(repo.join("all-files.txt")).unwrap(),
+        "notes.md\n"
+    );
+
+    fs::write(repo.join("generated_api.rs"), "").unwrap();
+    assert!(git(repo, &["add", "generated_api.rs"]).status.success());
+    assert!(commit(repo, "lib.rs").status.success());
+    assert_eq!(
+        fs::read_to_string(repo.join("rust-files.txt")).unwrap(),
+        "lib.rs\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repo.join("all-files.txt")).unwrap(),
+        "generated_api.rs\nlib.rs\n"
+    );
+}
+
+#[test]
+fn test_run_reports_commands_without_matching_files() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"[[commands]]
+id = "rust"
+command = "exit 1"
+files = ["*.rs"]
+"#,
+    )
+    .unwrap();
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("skipped   no matching files"));
+}
+
+#[test]
+fn test_commands_run_only_on_matching_pushed_files() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    let remote = TempDir::new().unwrap();
+    assert!(git(remote.path(), &["init", "-q", "--bare"])
+        .status
+        .success());
+    assert!(git(
+        repo,
+        &["remote", "add", "origin", remote.path().to_str().unwrap()]
+    )
+    .status
+    .success());
+    fs::write(
+        repo.join(".hookman/hooks/pre-push.toml"),
+        r#"[[commands]]
+id = "rust"
+command = "echo {files} > rust-files.txt"
+files = ["*.rs"]
+"#,
+    )
+    .unwrap();
+    assert!(hookman(repo, &["apply"]).status.success());
+
+    assert!(commit(repo, "lib.rs").status.success());
+    let push = || git(repo, &["push", "-q", "origin", "HEAD:refs/heads/main"]);
+    assert!(push().status.success());
+    assert_eq!(
+        fs::read_to_string(repo.join("rust-files.txt")).unwrap(),
+        "lib.rs\n"
+    );
+
+    fs::remove_file(repo.join("rust-files.txt")).unwrap();
+    assert!(commit(repo, "notes.md").status.success());
+    assert!(push().status.success());
+    assert!(!repo.join("rust-files.txt").exists());
+
+    assert!(commit(repo, "main.rs").status.success());
+    assert!(push().status.success());
+    assert_eq!(
+        fs::read_to_string(repo.join("rust-files.txt")).unwrap(),
+        "main.rs\n"
+    );
+}
+
+#[test]
+fn test_stash_unstaged_hides_unstaged_changes() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"stash_unstaged = true
+
+[[commands]]
+id = "check"
+command = "cat code.txt > seen.txt; test ! -e scratch.txt"
+"#,
+    )
+    .unwrap();
+    assert!(git(repo, &["add", ".hookman"]).status.success());
+    assert!(commit(repo, "code.txt").status.success());
+    assert!(hookman(repo, &["apply"]).status.success());
+
+    fs::write(repo.join("code.txt"), "staged\n").unwrap();
+    assert!(git(repo, &["add", "code.txt"]).status.success());
+    fs::write(repo.join("code.txt"), "staged\nunstaged\n").unwrap();
+    fs::write(repo.join("scratch.txt"), "untracked\n").unwrap();
+
+    let output = git(repo, &["commit", "-q", "-m", "partial"]);
+
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(
+        fs::read_to_string(repo.join("code.txt")).unwrap(),
+        "staged\nunstaged\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repo.join("scratch.txt")).unwrap(),
+        "untracked\n"
+    );
+    // seen.txt was itself untracked, so it was written after the stash.
+    assert_eq!(
+        fs::read_to_string(repo.join("seen.txt")).unwrap(),
+        "staged\n"
+    );
+    assert!(!repo.join(".git/hookman/stash").exists());
+}
+
+#[test]
+fn test_stash_unstaged_restores_after_failure_and_timeout() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"stash_unstaged = true
+fail_fast = false
+
+[[commands]]
+id = "fail"
+command = "exit 1"
+
+[[commands]]
+id = "slow"
+command = "sleep 30"
+timeout = "200ms"
+"#,
+    )
+    .unwrap();
+    assert!(git(repo, &["add", ".hookman"]).status.success());
+    assert!(commit(repo, "code.txt").status.success());
+    fs::write(repo.join("code.txt"), "unstaged\n").unwrap();
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        fs::read_to_string(repo.join("code.txt")).unwrap(),
+        "unstaged\n"
+    );
+    assert!(!repo.join(".git/hookman/stash").exists());
+}
+
+#[test]
+fn test_stash_unstaged_detects_conflicts() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"stash_unstaged = true
+
+[[commands]]
+id = "format"
+command = "echo formatted > code.txt"
+"#,
+    )
+    .unwrap();
+    assert!(git(repo, &["add", ".hookman"]).status.success());
+    assert!(commit(repo, "code.txt").status.success());
+    fs::write(repo.join("code.txt"), "unstaged\n").unwrap();
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Commands changed files with unstaged changes: code.txt"));
+    assert_eq!(
+        fs::read_to_string(repo.join("code.txt")).unwrap(),
+        "unstaged\n"
+    );
+}
+
+#[test]
+fn test_stash_unstaged_restores_untracked_when_patch_fails() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    // Staging the fix leaves the stashed patch nothing to apply to.
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"stash_unstaged = true
+
+[[commands]]
+id = "format"
+command = "echo fixed > f.txt && git add f.txt"
+fixer = true
+"#,
+    )
+    .unwrap();
+    assert!(git(repo, &["add", ".hookman"]).status.success());
+    assert!(commit(repo, "f.txt").status.success());
+    fs::write(repo.join("f.txt"), "staged\n").unwrap();
+    assert!(git(repo, &["add", "f.txt"]).status.success());
+    fs::write(repo.join("f.txt"), "staged\nunstaged\n").unwrap();
+    fs::write(repo.join("u.txt"), "untracked\n").unwrap();
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Summary:"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Failed to restore stashed changes; unstaged changes are saved in"),
+        "{}",
+        stderr
+    );
+    assert_eq!(
+        fs::read_to_string(repo.join("u.txt")).unwrap(),
+        "untracked\n"
+    );
+
+    // The patch is kept aside, and the next run isn't blocked by it.
+    assert!(!repo.join(".git/hookman/stash").exists());
+    let kept: Vec<_> = fs::read_dir(repo.join(".git/hookman"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.join("unstaged.patch").is_file())
+        .collect();
+    assert_eq!(kept.len(), 1);
+    let patch = fs::read_to_string(kept[0].join("unstaged.patch")).unwrap();
+    assert!(patch.contains("+unstaged"));
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+    assert!(output.status.success(), "{:?}", output);
+}
+
+#[test]
+fn test_stash_unstaged_fixer_keeps_unstaged_hunk() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"stash_unstaged = true
+
+[[commands]]
+id = "format"
+command = "echo fixed > f.txt"
+fixer = true
+"#,
+    )
+    .unwrap();
+    assert!(git(repo, &["add", ".hookman"]).status.success());
+    assert!(commit(repo, "f.txt").status.success());
+    fs::write(repo.join("f.txt"), "staged\n").unwrap();
+    assert!(git(repo, &["add", "f.txt"]).status.success());
+    fs::write(repo.join("f.txt"), "staged\nunstaged\n").unwrap();
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    // The file had unstaged changes, so the fix is left for review rather
+    // than staged over them.
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("review and re-stage: f.txt"), "{}", stderr);
+    assert_eq!(
+        fs::read_to_string(repo.join("f.txt")).unwrap(),
+        "staged\nunstaged\n"
+    );
+    let staged = git(repo, &["show", ":f.txt"]);
+    assert_eq!(String::from_utf8_lossy(&staged.stdout), "staged\n");
+}
+
+#[test]
+fn test_fixer_restages_modified_files() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"[[commands]]
+id = "format"
+command = "for f in {files}; do echo formatted > $f; done"
+fixer = true
+"#,
+    )
+    .unwrap();
+    assert!(hookman(repo, &["apply"]).status.success());
+    fs::write(repo.join("untouched.txt"), "not staged\n").unwrap();
+
+    let output = commit(repo, "code.txt");
+
+    assert!(output.status.success(), "{:?}", output);
+    let committed = git(repo, &["show", "HEAD:code.txt"]);
+    assert_eq!(String::from_utf8_lossy(&committed.stdout), "formatted\n");
+    // Files that weren't staged are never added.
+    let status = git(repo, &["status", "--porcelain"]);
+    assert!(String::from_utf8_lossy(&status.stdout).contains("?? untouched.txt"));
+}
+
+#[test]
+fn test_parallel_fixers_run_alone() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    // Run together, the slow fixer would also see the fast one's change.
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"parallel = true
+concurrency = 2
+
+[[commands]]
+id = "slow"
+command = "sleep 1; echo fixed > a.txt"
+fixer = true
+
+[[commands]]
+id = "fast"
+command = "echo fixed > b.txt"
+fixer = true
+"#,
+    )
+    .unwrap();
+    assert!(hookman(repo, &["apply"]).status.success());
+    fs::write(repo.join("a.txt"), "a\n").unwrap();
+    fs::write(repo.join("b.txt"), "b\n").unwrap();
+    assert!(git(repo, &["add", "a.txt", "b.txt"]).status.success());
+
+    let output = git(repo, &["commit", "-q", "-m", "test commit"]);
+
+    assert!(output.status.success(), "{:?}", output);
+    // Git passes hook output on to stderr.
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("modified by slow: a.txt\n"), "{}", stderr);
+    assert!(stderr.contains("modified by fast: b.txt\n"), "{}", stderr);
+    for file in ["a.txt", "b.txt"] {
+        let committed = git(repo, &["show", &format!("HEAD:{}", file)]);
+        assert_eq!(String::from_utf8_lossy(&committed.stdout), "fixed\n");
+    }
+}
+
+#[test]
+fn test_fixer_fail_mode_asks_for_review() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"fix_mode = "fail"
+
+[[commands]]
+id = "format"
+command = "echo formatted > code.txt"
+fixer = true
+"#,
+    )
+    .unwrap();
+    assert!(hookman(repo, &["apply"]).status.success());
+
+    let output = commit(repo, "code.txt");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("files were modified by format, review and re-stage: code.txt"));
+    assert_eq!(
+        fs::read_to_string(repo.join("code.txt")).unwrap(),
+        "formatted\n"
+    );
+}
+
+#[test]
+fn test_fixer_does_not_restage_partially_staged_files() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"[[commands]]
+id = "format"
+command = "echo formatted >> code.txt"
+fixer = true
+"#,
+    )
+    .unwrap();
+    fs::write(repo.join("code.txt"), "staged\n").unwrap();
+    assert!(git(repo, &["add", "code.txt"]).status.success());
+    fs::write(repo.join("code.txt"), "staged\nunstaged\n").unwrap();
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("files were modified by format, review and re-stage: code.txt"));
+    let staged = git(repo, &["show", ":code.txt"]);
+    assert_eq!(String::from_utf8_lossy(&staged.stdout), "staged\n");
+}
+
+#[test]
+fn test_hookman_skip_skips_listed_commands() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    add(repo, "pre-commit", "exit 1", "lint");
+    add(repo, "pre-commit", "exit 1", "test");
+    add(repo, "pre-commit", "touch format-ran", "format");
+
+    let output = hookman_command(repo)
+        .env("HOOKMAN_SKIP", "lint, test")
+        .args(["run", "pre-commit"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    assert!(repo.join("format-ran").exists());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("- lint    skipped   listed in HOOKMAN_SKIP"));
+    assert!(stdout.contains("- test    skipped   listed in HOOKMAN_SKIP"));
+}
+
+#[test]
+fn test_hookman_zero_disables_hooks() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    add(repo, "pre-commit", "exit 1", "block");
+    assert!(hookman(repo, &["apply"]).status.success());
+
+    assert!(!commit(repo, "a.txt").status.success());
+
+    fs::write(repo.join("b.txt"), "b").unwrap();
+    assert!(git(repo, &["add", "b.txt"]).status.success());
+    let output = std::process::Command::new("git")
+        .current_dir(repo)
+        .env("HOOKMAN", "0")
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("GIT_CONFIG_GLOBAL", "/dev/null")
+        .args([
+            "-c",
+            "user.name=Hookman",
+            "-c",
+            "user.email=hookman@example.com",
+        ])
+        .args(["commit", "-q", "-m", "skip hooks"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+
+    let output = hookman_command(repo)
+        .env("HOOKMAN", "0")
+        .args(["run", "pre-commit"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("HOOKMAN=0"));
+}
+
+#[test]
+fn test_when_branch_env_and_ci_conditions() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    assert!(git(repo, &["checkout", "-q", "-b", "release/1.0"])
+        .status
+        .success());
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"[[commands]]
+id = "release"
+command = "touch release-ran"
+when = { branch = ["main", "release/*"] }
+
+[[commands]]
+id = "main-only"
+command = "touch main-ran"
+when = { branch = ["main"] }
+
+[[commands]]
+id = "deploy"
+command = "touch deploy-ran"
+when = { env = { DEPLOY = "1" } }
+
+[[commands]]
+id = "local"
+command = "touch local-ran"
+when = { ci = false }
+"#,
+    )
+    .unwrap();
+
+    let output = hookman_command(repo)
+        .env("CI", "true")
+        .env_remove("DEPLOY")
+        .args(["run", "pre-commit"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    assert!(repo.join("release-ran").exists());
+    assert!(!repo.join("main-ran").exists());
+    assert!(!repo.join("deploy-ran").exists());
+    assert!(!repo.join("local-ran").exists());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("skipped   branch is not main"));
+    assert!(stdout.contains("skipped   DEPLOY is not 1"));
+    assert!(stdout.contains("skipped   running in CI"));
+}
+
+#[test]
+fn test_when_remote_and_pushed_branch() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    let remote = TempDir::new().unwrap();
+    assert!(git(remote.path(), &["init", "-q", "--bare"])
+        .status
+        .success());
+    let url = remote.path().to_str().unwrap();
+    assert!(git(repo, &["remote", "add", "origin", url])
+        .status
+        .success());
+    fs::write(
+        repo.join(".hookman/hooks/pre-push.toml"),
+        r#"[[commands]]
+id = "origin-main"
+command = "touch origin-main-ran"
+when = { remote = "origin", branch = ["main"] }
+"#,
+    )
+    .unwrap();
+    assert!(hookman(repo, &["apply"]).status.success());
+    assert!(commit(repo, "a.txt").status.success());
+
+    assert!(git(repo, &["push", "-q", url, "HEAD:refs/heads/main"])
+        .status
+        .success());
+    assert!(!repo.join("origin-main-ran").exists());
+
+    assert!(
+        git(repo, &["push", "-q", "origin", "HEAD:refs/heads/topic"])
+            .status
+            .success()
+    );
+    assert!(!repo.join("origin-main-ran").exists());
+
+    assert!(commit(repo, "b.txt").status.success());
+    assert!(git(repo, &["push", "-q", "origin", "HEAD:refs/heads/main"])
+        .status
+        .success());
+    assert!(repo.join("origin-main-ran").exists());
+}
+
+#[test]
+fn test_when_checkout_type() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/post-checkout.toml"),
+        r#"[[commands]]
+id = "branch-switch"
+command = "touch branch-checkout-ran"
+when = { checkout_type = "branch" }
+"#,
+    )
+    .unwrap();
+    assert!(commit(repo, "a.txt").status.success());
+    assert!(hookman(repo, &["apply"]).status.success());
+
+    assert!(git(repo, &["checkout", "-q", "--", "a.txt"])
+        .status
+        .success());
+    assert!(!repo.join("branch-checkout-ran").exists());
+
+    assert!(git(repo, &["checkout", "-q", "-b", "topic"])
+        .status
+        .success());
+    assert!(repo.join("branch-checkout-ran").exists());
+}
+
+#[test]
+fn test_command_env_and_cwd() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::create_dir(repo.join("frontend")).unwrap();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"env = { STAGE = "hook" }
+
+[[commands]]
+id = "frontend-test"
+command = "echo \"$STAGE $NODE_ENV\" > out.txt"
+cwd = "frontend"
+env = { NODE_ENV = "test" }
+"#,
+    )
+    .unwrap();
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(
+        fs::read_to_string(repo.join("frontend/out.txt")).unwrap(),
+        "hook test\n"
+    );
+
+    let output = hookman(repo, &["list", "pre-commit"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Environment: STAGE=hook"));
+    assert!(stdout.contains("in: frontend"));
+    assert!(stdout.contains("env: NODE_ENV=test"));
+}
+
+#[test]
+fn test_cwd_outside_repository_is_rejected() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        "[[commands]]\nid = \"escape\"\ncommand = \"true\"\ncwd = \"../elsewhere\"\n",
+    )
+    .unwrap();
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Working directory '../elsewhere' of command 'escape'"));
+}
+
+#[test]
+fn test_shell_and_script() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/config.toml"),
+        format!(
+            "version = \"{}\"\nshell = \"bash\"\n",
+            env!("CARGO_PKG_VERSION")
+        ),
+    )
+    .unwrap();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"[[commands]]
+id = "bash-default"
+command = "[[ -n $BASH_VERSION ]] && touch bash-ran"
+
+[[commands]]
+id = "python-check"
+shell = "python3"
+script = """
+import os
+open("python-ran", "w").write(os.environ["HOOKMAN_HOOK"])
+"""
+"#,
+    )
+    .unwrap();
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+
+    assert!(output.status.success(), "{:?}", output);
+    assert!(repo.join("bash-ran").exists());
+    assert_eq!(
+        fs::read_to_string(repo.join("python-ran")).unwrap(),
+        "pre-commit"
+    );
+
+    let output = hookman(repo, &["list", "pre-commit"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[python-check] script:"));
+    assert!(stdout.contains("      import os"));
+    assert!(stdout.contains("shell: python3"));
+}
+
+#[test]
+fn test_add_rejects_unsafe_id() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+
+    let output = hookman(
+        repo,
+        &["add", "pre-commit", "true", "--id", "a\"; rm -rf ~; \""],
+    );
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("IDs may only contain"));
+    assert!(!repo.join(".hookman/hooks/pre-commit.toml").exists());
+}
+
+#[test]
+fn test_edit_command_in_place() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"[[commands]]
+id = "fmt"
+command = "cargo fmt"
+description = "Format"
+
+[[commands]]
+id = "test"
+command = "cargo test"
+depends_on = ["fmt"]
+"#,
+    )
+    .unwrap();
+
+    let output = hookman(
+        repo,
+        &[
+            "edit",
+            "pre-commit",
+            "fmt",
+            "--new-id",
+            "format",
+            "--command",
+            "cargo fmt --check",
+            "--timeout",
+            "1m",
+            "--env",
+            "RUSTFMT=rustfmt",
+        ],
+    );
+
+    assert!(output.status.success(), "{:?}", output);
+    let content = fs::read_to_string(repo.join(".hookman/hooks/pre-commit.toml")).unwrap();
+    let format = content.find("id = \"format\"").unwrap();
+    assert!(format < content.find("id = \"test\"").unwrap());
+    assert!(content.contains("command = \"cargo fmt --check\""));
+    assert!(content.contains("description = \"Format\""));
+    assert!(content.contains("timeout = \"1m\""));
+    assert!(content.contains("depends_on = [\"format\"]"));
+    assert!(content.contains("RUSTFMT = \"rustfmt\""));
+
+    let output = hookman(repo, &["edit", "pre-commit", "format", "--new-id", "test"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already exists"));
+}
+
+#[test]
+fn test_edit_interactively() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"[[commands]]
+id = "lint"
+command = "make lint"
+cwd = "web"
+when = { branch = ["main"] }
+env = { STRICT = "1" }
+"#,
+    )
+    .unwrap();
+
+    let output = hookman_command(repo)
+        .env("EDITOR", "sed -i.bak 's/make lint/make lint-all/'")
+        .env_remove("VISUAL")
+        .args(["edit", "pre-commit", "lint"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    let content = fs::read_to_string(repo.join(".hookman/hooks/pre-commit.toml")).unwrap();
+    assert!(content.contains("command = \"make lint-all\""));
+    assert!(content.contains("cwd = \"web\""));
+    assert!(content.contains("STRICT = \"1\""));
+    assert!(content.contains("\"main\""));
+
+    // An invalid edit is reported, and declining to edit again leaves the
+    // hook unchanged.
+    let output = hookman_command(repo)
+        .env(
+            "EDITOR",
+            "sed -i.bak 's/^cwd = .*/cwd = \"..\\/elsewhere\"/'",
+        )
+        .env_remove("VISUAL")
+        .args(["edit", "pre-commit", "lint"])
+        .stdin(std::process::Stdio::null())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Working directory"));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Edit cancelled"));
+    assert_eq!(
+        fs::read_to_string(repo.join(".hookman/hooks/pre-commit.toml")).unwrap(),
+        content
+    );
+}
+
+#[test]
+fn test_move_and_add_at_position() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    add(repo, "pre-commit", "echo a >> order.txt", "a");
+    add(repo, "pre-commit", "echo b >> order.txt", "b");
+    add(repo, "pre-commit", "echo c >> order.txt", "c");
+
+    let output = hookman(repo, &["move", "pre-commit", "c", "--before", "a"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("to position 1 of 3"));
+
+    let output = hookman(
+        repo,
+        &[
+            "add",
+            "pre-commit",
+            "echo d >> order.txt",
+            "--id",
+            "d",
+            "--position",
+            "2",
+        ],
+    );
+    assert!(output.status.success(), "{:?}", output);
+
+    let output = hookman(repo, &["move", "pre-commit", "a", "--to", "9"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Position 9 is out of range for hook 'pre-commit', use 1 to 4"));
+
+    // Adding can also append, one past the last command.
+    let output = hookman(
+        repo,
+        &["add", "pre-commit", "true", "--id", "e", "--position", "9"],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("use 1 to 5"));
+
+    let output = hookman(
+        repo,
+        &["move", "pre-commit", "a", "--before", "b", "--to", "1"],
+    );
+    assert!(!output.status.success());
+
+    assert!(hookman(repo, &["run", "pre-commit"]).status.success());
+    assert_eq!(
+        fs::read_to_string(repo.join("order.txt")).unwrap(),
+        "c\nd\na\nb\n"
+    );
+}
+
+#[test]
+fn test_disable_and_enable_command() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    add(repo, "pre-commit", "touch lint-ran", "lint");
+    add(repo, "pre-commit", "touch test-ran", "test");
+    assert!(hookman(repo, &["apply"]).status.success());
+
+    let output = hookman(repo, &["disable", "pre-commit", "lint"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Disabled command 'lint'"));
+    assert!(
+        fs::read_to_string(repo.join(".hookman/hooks/pre-commit.toml"))
+            .unwrap()
+            .contains("enabled = false")
+    );
+
+    let output = hookman(repo, &["run", "pre-commit"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(!repo.join("lint-ran").exists());
+    assert!(repo.join("test-ran").exists());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("skipped   disabled"));
+
+    let stdout = String::from_utf8_lossy(&hookman(repo, &["list"]).stdout).into_owned();
+    assert!(stdout.contains("[lint] touch lint-ran (disabled)"));
+    let stdout = String::from_utf8_lossy(&hookman(repo, &["status"]).stdout).into_owned();
+    assert!(stdout.contains("1 commands, 1 disabled"));
+
+    let output = hookman(repo, &["disable", "pre-commit", "lint"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("already disabled"));
+
+    assert!(hookman(repo, &["enable", "pre-commit", "lint"])
+        .status
+        .success());
+    assert!(hookman(repo, &["run", "pre-commit"]).status.success());
+    assert!(repo.join("lint-ran").exists());
+    assert!(
+        !fs::read_to_string(repo.join(".hookman/hooks/pre-commit.toml"))
+            .unwrap()
+            .contains("enabled")
+    );
+}
+
+#[test]
+fn test_mv_and_cp_between_hooks() {
+    let temp_dir = setup_git_repo();
+    let repo = temp_dir.path();
+    fs::write(
+        repo.join(".hookman/hooks/pre-commit.toml"),
+        r#"[[commands]]
+id = "lint"
+command = "make lint"
+description = "Lint"
+timeout = "2m"
+
+[[commands]]
+id = "test"
+command = "make test"
+files = ["*.rs"]
+env = { RUST_LOG = "warn" }
+"#,
+    )
+    .unwrap();
+    add(repo, "pre-push", "make lint", "lint");
+
+    let output = hookman(repo, &["mv", "pre-commit", "lint", "pre-push"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Command with ID 'lint' already exists in hook 'pre-push'"));
+
+    let output = hookman(
+        repo,
+        &[
+            "mv",
+            "pre-commit",
+            "lint",
+            "pre-push",
+            "--new-id",
+            "lint-strict",
+        ],
+    );
+    assert!(output.status.success(), "{:?}", output);
+    let pre_commit = fs::read_to_string(repo.join(".hookman/hooks/pre-commit.toml")).unwrap();
+    let pre_push = fs::read_to_string(repo.join(".hookman/hooks/pre-push.toml")).unwrap();
+    assert!(!pre_commit.contains("make lint"));
+    assert!(pre_push.contains("id = \"lint-strict\""));
+    assert!(pre_push.contains("description = \"Lint\""));
+    assert!(pre_push.contains("timeout = \"2m\""));
+
+    let output = hookman(repo, &["cp", "pre-commit", "test", "pre-push"]);
+    assert!(output.status.success(), "{:?}", output);
+    let pre_commit = fs::read_to_string(repo.join(".hookman/hooks/pre-commit.toml")).unwrap();
+    let pre_push = fs::read_to_string(repo.join(".hookman/hooks/pre-push.toml")).unwrap();
+    assert!(pre_commit.contains("id = \"test\""));
+    assert!(pre_push.contains("id = \"test\""));
+    assert!(pre_push.contains("files = [\"*.rs\"]"));
+    assert!(pre_push.contains("RUST_LOG = \"warn\""));
+
+    let output = hookman(repo, &["mv", "pre-commit", "missing", "pre-push"]);
+    assert!(!output.status.success());
+
+    let output = hookman(repo, &["mv", "pre-commit", "test", "pre-commit"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Command 'test' is already in the pre-commit hook"));
+
+    add(repo, "pre-commit", "make check", "check");
+    let output = hookman(
+        repo,
+        &["edit", "pre-commit", "check", "--depends-on", "test"],
+    );
+    assert!(output.status.success(), "{:?}", output);
+    let output = hookman(repo, &["mv", "pre-commit", "test", "post-commit"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Command 'test' can't be moved while other commands depend on it: check"));
+}